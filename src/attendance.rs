@@ -0,0 +1,266 @@
+//! Attendance and demographics of a tournament, computed from its participants and their entrants.
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use crate::{
+    enums::*,
+    execute_paginated_query,
+    structs::*,
+    Vars,
+};
+
+/// Attendance of a tournament and its events.
+///
+/// Attendees are the tournament's participants, matched by player so an attendee listed twice is only counted once.
+/// Contact info is only returned to the tournament's admins, so the location breakdown is empty for other tokens.
+#[derive(Clone, Default)]
+pub struct AttendanceReport {
+
+    /// The number of unique attendees.
+    pub attendees:              i64,
+
+    /// The number of attendees entered in more than one event.
+    pub multi_event_attendees:  i64,
+
+    /// The number of attendees who are checked in.
+    pub checked_in:             i64,
+
+    /// The number of attendees entered in at least one online event.
+    pub online_attendees:       i64,
+
+    /// The number of attendees entered in at least one offline event.
+    pub offline_attendees:      i64,
+
+    /// The number of entrants in online events.
+    pub online_entrants:        i64,
+
+    /// The number of entrants in offline events.
+    pub offline_entrants:       i64,
+
+    /// The number of entrants in each event, from most to least.
+    pub events:                 Vec<(GGEvent, i64)>,
+
+    /// The number of attendees from each country, from most to least.
+    pub countries:              Vec<(String, i64)>,
+
+    /// The number of attendees from each state, by country and state, from most to least.
+    pub states:                 Vec<(String, String, i64)>,
+
+    /// The number of attendees without a country in their contact info.
+    pub unknown_location:       i64,
+
+}
+
+impl AttendanceReport {
+
+    /// Creates a report from a tournament's participants.
+    ///
+    /// Each participant needs their player, check in, contact info and entrants, with the event of each entrant and whether it's online.
+    pub fn new(participants: &[GGParticipant]) -> AttendanceReport {
+        // Participants of the same player are merged into one attendee.
+        let mut attendees: Vec<GGParticipant> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for participant in participants {
+            let key = if participant.player().id() != 0 {
+                format!("player {}", participant.player().id())
+            } else {
+                format!("participant {}", participant.id())
+            };
+            match indices.get(&key) {
+                Some(index) => {
+                    let attendee = &mut attendees[*index];
+                    attendee.checked_in = Some(attendee.checked_in() || participant.checked_in());
+                    attendee.entrants = Some([attendee.entrants(), participant.entrants()].concat());
+                    if attendee.contact_info.is_none() {
+                        attendee.contact_info = participant.contact_info.clone();
+                    }
+                },
+                None => {
+                    indices.insert(key, attendees.len());
+                    attendees.push(participant.clone());
+                },
+            }
+        }
+
+        let mut report = AttendanceReport { attendees: attendees.len() as i64, ..Default::default() };
+        let mut events: Vec<(GGEvent, i64)> = Vec::new();
+        let mut entrants: HashSet<String> = HashSet::new();
+        let mut countries: HashMap<String, i64> = HashMap::new();
+        let mut states: HashMap<(String, String), i64> = HashMap::new();
+
+        for attendee in &attendees {
+            if attendee.checked_in() {
+                report.checked_in += 1;
+            }
+
+            let mut event_ids: HashSet<String> = HashSet::new();
+            let (mut online, mut offline) = (false, false);
+            for entrant in attendee.entrants() {
+                let event = entrant.event();
+                event_ids.insert(event.id().to_string());
+                if event.is_online() { online = true } else { offline = true }
+                // Team entrants are shared between attendees, so each is only counted once.
+                if !entrants.insert(entrant.id().to_string()) {
+                    continue;
+                }
+                if event.is_online() { report.online_entrants += 1 } else { report.offline_entrants += 1 }
+                match events.iter_mut().find(|(other, _)| other.id() == event.id()) {
+                    Some((_, count)) => *count += 1,
+                    None => events.push((event, 1)),
+                }
+            }
+            if event_ids.len() > 1 {
+                report.multi_event_attendees += 1;
+            }
+            if online {
+                report.online_attendees += 1;
+            }
+            if offline {
+                report.offline_attendees += 1;
+            }
+
+            let contact_info = attendee.contact_info();
+            if contact_info.country().is_empty() {
+                report.unknown_location += 1;
+                continue;
+            }
+            *countries.entry(contact_info.country()).or_insert(0) += 1;
+            if !contact_info.state().is_empty() {
+                *states.entry((contact_info.country(), contact_info.state())).or_insert(0) += 1;
+            }
+        }
+
+        events.sort_by_key(|(event, count)| (-count, event.name()));
+        report.events = events;
+        report.countries = countries.into_iter().collect();
+        report.countries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        report.states = states.into_iter().map(|((country, state), count)| (country, state, count)).collect();
+        report.states.sort_by(|a, b| b.2.cmp(&a.2).then((&a.0, &a.1).cmp(&(&b.0, &b.1))));
+        return report;
+    }
+
+    /// Fetches the participants of a tournament and creates a report.
+    ///
+    /// Returns the error message if the query failed.
+    pub async fn load(tournament_id: GGID, token: &str) -> Result<AttendanceReport, String> {
+
+        let query = r#"
+        query TournamentAttendance($id: ID!, $page: Int!, $per_page: Int!) {
+            tournament(id: $id) {
+                id
+                participants(query: { page: $page, perPage: $per_page }) {
+                    pageInfo {
+                        totalPages
+                    }
+                    nodes {
+                        id
+                        gamerTag
+                        checkedIn
+                        player {
+                            id
+                        }
+                        contactInfo {
+                            id
+                            country
+                            state
+                        }
+                        entrants {
+                            id
+                            event {
+                                id
+                                name
+                                isOnline
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        "#;
+
+        // Each participant brings their entrants and events, so pages are kept small to stay under the object limit.
+        let vars = |page| Vars { id: tournament_id.clone(), slug: "".to_string(), page: page, per_page: 25 };
+
+        let response = execute_paginated_query(token, query, vars, |data| {
+            let participants = data.tournament.as_mut()?.participants.as_mut()?;
+            Some((participants.page_info().total_pages(), &mut participants.nodes))
+        }).await;
+        match response {
+            GGResponse::Data(data) => return Ok(AttendanceReport::new(&data.tournament().participants())),
+            GGResponse::Error(e) => return Err(e),
+        }
+    }
+
+    /// Returns the share of attendees who are checked in.
+    ///
+    /// Returns zero if there are no attendees.
+    pub fn checked_in_rate(&self) -> f64 {
+        if self.attendees == 0 {
+            return 0.0;
+        }
+        return self.checked_in as f64 / self.attendees as f64;
+    }
+
+    /// Returns the share of entrants in online events.
+    ///
+    /// Returns zero if there are no entrants.
+    pub fn online_rate(&self) -> f64 {
+        let entrants = self.online_entrants + self.offline_entrants;
+        if entrants == 0 {
+            return 0.0;
+        }
+        return self.online_entrants as f64 / entrants as f64;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(id: i64, player_id: i64, checked_in: bool, location: Option<(&str, &str)>, entrants: Vec<(i64, i64, bool)>) -> GGParticipant {
+        return GGParticipant {
+            id: Some(id),
+            checked_in: Some(checked_in),
+            player: Some(Box::new(GGPlayer { id: Some(player_id), ..Default::default() })),
+            contact_info: location.map(|(country, state)| Box::new(GGContactInfo {
+                country: Some(country.to_string()),
+                state: Some(state.to_string()),
+                ..Default::default()
+            })),
+            entrants: Some(entrants.into_iter().map(|(entrant_id, event_id, is_online)| GGEntrant {
+                id: Some(GGID::Int(entrant_id)),
+                event: Some(Box::new(GGEvent { id: Some(GGID::Int(event_id)), name: Some(format!("Event {}", event_id)), is_online: Some(is_online), ..Default::default() })),
+                ..Default::default()
+            }).collect()),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn attendance_is_counted_per_attendee() {
+        let report = AttendanceReport::new(&[
+            participant(1, 10, true, Some(("US", "CA")), vec![(100, 1, false), (101, 2, true)]),
+            participant(2, 20, false, Some(("US", "NY")), vec![(102, 1, false)]),
+            participant(3, 30, true, Some(("Canada", "")), vec![(103, 1, false)]),
+            participant(4, 40, true, None, vec![(104, 2, true)]),
+            // The same player listed again, with an entrant already counted.
+            participant(5, 10, false, None, vec![(100, 1, false)]),
+        ]);
+
+        assert_eq!((report.attendees, report.multi_event_attendees, report.checked_in), (4, 1, 3));
+        assert_eq!(report.checked_in_rate(), 0.75);
+        assert_eq!((report.online_attendees, report.offline_attendees), (2, 3));
+        assert_eq!((report.online_entrants, report.offline_entrants), (2, 3));
+        assert_eq!(report.online_rate(), 0.4);
+
+        let events: Vec<(String, i64)> = report.events.iter().map(|(event, count)| (event.name(), *count)).collect();
+        assert_eq!(events, vec![("Event 1".to_string(), 3), ("Event 2".to_string(), 2)]);
+        assert_eq!(report.countries, vec![("US".to_string(), 2), ("Canada".to_string(), 1)]);
+        assert_eq!(report.states, vec![("US".to_string(), "CA".to_string(), 1), ("US".to_string(), "NY".to_string(), 1)]);
+        assert_eq!(report.unknown_location, 1);
+    }
+}
@@ -0,0 +1,307 @@
+//! A graph of the sets in an elimination bracket, used to follow where entrants go after each set.
+//!
+//! The graph is built from the prerequisites of each set's slots: a slot filled by the winner or loser of another set is an edge from that set.
+
+use std::collections::{
+    HashMap,
+    HashSet,
+    VecDeque,
+};
+
+use crate::{
+    enums::*,
+    get_phase_group_sets,
+    inputs::*,
+    structs::*,
+};
+
+/// Which entrant of a set moves along an edge.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Progression {
+
+    Winner,
+    Loser,
+
+}
+
+/// An edge between two sets in a bracket.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BracketEdge {
+
+    /// The set the entrant comes from.
+    pub from:           GGID,
+
+    /// The set the entrant goes to.
+    pub to:             GGID,
+
+    /// Whether the winner or loser of the set the entrant comes from moves along the edge.
+    pub progression:    Progression,
+
+    /// The index of the slot the entrant is placed in.
+    pub slot_index:     usize,
+
+}
+
+/// The sets of a single or double elimination phase group, and how entrants move between them.
+///
+/// Sets are identified by id. Rounds are positive on the winners side and negative on the losers side, as start.gg numbers them.
+#[derive(Clone, Default)]
+pub struct Bracket {
+
+    sets:       Vec<GGSet>,
+    indices:    HashMap<String, usize>,
+    edges:      Vec<BracketEdge>,
+
+}
+
+impl Bracket {
+
+    /// Builds a bracket from the sets of a phase group.
+    ///
+    /// The slots of each set need their prerequisite id, type and placement, see get_phase_group_sets().
+    pub fn new(sets: Vec<GGSet>) -> Bracket {
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for (index, set) in sets.iter().enumerate() {
+            indices.insert(set.id().to_string(), index);
+        }
+
+        let mut edges: Vec<BracketEdge> = Vec::new();
+        for set in &sets {
+            for (slot_index, slot) in set.slots().iter().enumerate() {
+                if slot.prereq_type() != "set" || !indices.contains_key(&slot.prereq_id()) {
+                    continue;
+                }
+                let progression = match slot.prereq_placement() {
+                    1 => Progression::Winner,
+                    2 => Progression::Loser,
+                    _ => continue,
+                };
+                edges.push(BracketEdge {
+                    from:           sets[indices[&slot.prereq_id()]].id(),
+                    to:             set.id(),
+                    progression:    progression,
+                    slot_index:     slot_index,
+                });
+            }
+        }
+
+        return Bracket { sets: sets, indices: indices, edges: edges };
+    }
+
+    /// Fetches the sets of a phase group and builds its bracket.
+    ///
+    /// Returns the error message if the query failed.
+    pub async fn load(phase_group_id: GGID, token: &str) -> Result<Bracket, String> {
+        let filters = SetFilters { show_byes: Some(true), ..Default::default() };
+        match get_phase_group_sets(phase_group_id, filters, token).await {
+            GGResponse::Data(data) => return Ok(Bracket::new(data.phase_group().sets())),
+            GGResponse::Error(e) => return Err(e),
+        }
+    }
+
+    /// Returns a set in the bracket by id.
+    pub fn set(&self, id: &GGID) -> Option<&GGSet> {
+        return self.indices.get(&id.to_string()).map(|index| &self.sets[*index]);
+    }
+
+    /// Returns every set in the bracket, in the order they were given.
+    pub fn sets(&self) -> &[GGSet] {
+        return &self.sets;
+    }
+
+    /// Returns every edge in the bracket.
+    pub fn edges(&self) -> &[BracketEdge] {
+        return &self.edges;
+    }
+
+    /// Returns the edges into a set, one for each slot filled by another set.
+    pub fn prerequisites(&self, id: &GGID) -> Vec<&BracketEdge> {
+        return self.edges.iter().filter(|edge| edge.to == *id).collect();
+    }
+
+    /// Returns the edge the winner or loser of a set moves along.
+    ///
+    /// Returns None if the entrant doesn't play another set in the bracket, such as the loser of a set on the losers side.
+    pub fn next(&self, id: &GGID, progression: Progression) -> Option<&BracketEdge> {
+        return self.edges.iter().find(|edge| edge.from == *id && edge.progression == progression);
+    }
+
+    /// Returns the set the winner of a set plays next.
+    pub fn winner_destination(&self, id: &GGID) -> Option<&GGSet> {
+        return self.next(id, Progression::Winner).and_then(|edge| self.set(&edge.to));
+    }
+
+    /// Returns the set the loser of a set plays next.
+    ///
+    /// Returns None if the loser is eliminated.
+    pub fn loser_destination(&self, id: &GGID) -> Option<&GGSet> {
+        return self.next(id, Progression::Loser).and_then(|edge| self.set(&edge.to));
+    }
+
+    /// Returns if losing a set on the winners side sends the entrant to a losers side.
+    pub fn is_double_elimination(&self) -> bool {
+        return self.sets.iter().any(|set| set.round() < 0);
+    }
+
+    /// Returns the grand finals reset, the set both entrants of the grand finals go to.
+    ///
+    /// Returns None if the bracket doesn't have a reset.
+    pub fn grand_finals_reset(&self) -> Option<&GGSet> {
+        return self.sets.iter().find(|set| {
+            let prerequisites = self.prerequisites(&set.id());
+            prerequisites.len() == 2 && prerequisites[0].from == prerequisites[1].from
+        });
+    }
+
+    /// Returns the grand finals, or the final of a single elimination bracket.
+    ///
+    /// Sets that the winner doesn't move on from are considered, other than the reset, and the one with the highest winners side round is chosen.
+    /// This leaves out a match for third place, which is played on an earlier round.
+    pub fn grand_finals(&self) -> Option<&GGSet> {
+        if let Some(reset) = self.grand_finals_reset() {
+            return self.set(&self.prerequisites(&reset.id())[0].from);
+        }
+        return self.sets.iter()
+            .filter(|set| self.next(&set.id(), Progression::Winner).is_none())
+            .max_by_key(|set| set.round());
+    }
+
+    /// Returns the sets the winner of a set plays on the way to the grand finals, starting with the set itself.
+    ///
+    /// Returns an empty vector if the set isn't in the bracket or the winner never reaches the grand finals.
+    pub fn path_to_grand_finals(&self, id: &GGID) -> Vec<&GGSet> {
+        let grand_finals = match self.grand_finals() {
+            Some(set) => set.id(),
+            None => return Vec::new(),
+        };
+        let mut path: Vec<&GGSet> = Vec::new();
+        let mut current = self.set(id);
+        while let Some(set) = current {
+            path.push(set);
+            if set.id() == grand_finals {
+                return path;
+            }
+            // A malformed bracket could loop, so the path is never longer than the bracket.
+            if path.len() > self.sets.len() {
+                break;
+            }
+            current = self.winner_destination(&set.id());
+        }
+        return Vec::new();
+    }
+
+    /// Returns the sets an entrant could still play, depending on the results of the sets before them.
+    ///
+    /// Starts from the entrant's unfinished sets, or the set they move on to after their last result, and follows the bracket from there.
+    /// Sets that have already been completed by other entrants are left out.
+    pub fn remaining_sets(&self, entrant_id: &GGID) -> Vec<&GGSet> {
+        let completed = GGActivityState::Completed as i64;
+        let entrant = entrant_id.to_string();
+        let mut queue: VecDeque<GGID> = VecDeque::new();
+        for set in &self.sets {
+            let has_entrant = set.slots().iter().any(|slot| slot.entrant.is_some() && slot.entrant().id().to_string() == entrant);
+            if !has_entrant {
+                continue;
+            }
+            if set.state() != completed {
+                queue.push_back(set.id());
+                continue;
+            }
+            let progression = if set.winner_id().to_string() == entrant { Progression::Winner } else { Progression::Loser };
+            if let Some(edge) = self.next(&set.id(), progression) {
+                queue.push_back(edge.to.clone());
+            }
+        }
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut result: Vec<&GGSet> = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            let set = match self.set(&id) {
+                Some(set) => set,
+                None => continue,
+            };
+            if set.state() == completed || !seen.insert(id.to_string()) {
+                continue;
+            }
+            result.push(set);
+            for edge in self.edges.iter().filter(|edge| edge.from == id) {
+                queue.push_back(edge.to.clone());
+            }
+        }
+        result.sort_by_key(|set| self.indices[&set.id().to_string()]);
+        return result;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(prereq: Option<(i64, i64)>, entrant_id: Option<i64>) -> GGSetSlot {
+        return GGSetSlot {
+            prereq_id: prereq.map(|(id, _)| id.to_string()),
+            prereq_type: Some(if prereq.is_some() { "set" } else { "seed" }.to_string()),
+            prereq_placement: prereq.map(|(_, placement)| placement),
+            entrant: entrant_id.map(|id| Box::new(GGEntrant { id: Some(GGID::Int(id)), ..Default::default() })),
+            ..Default::default()
+        };
+    }
+
+    fn set(id: i64, round: i64, slots: Vec<GGSetSlot>) -> GGSet {
+        return GGSet { id: Some(GGID::Int(id)), round: Some(round), slots: Some(slots), ..Default::default() };
+    }
+
+    /// A four entrant double elimination bracket, where entrant 100 has beaten entrant 400.
+    fn double_elimination() -> Bracket {
+        let mut first = set(1, 1, vec![slot(None, Some(100)), slot(None, Some(400))]);
+        first.state = Some(GGActivityState::Completed as i64);
+        first.winner_id = Some(100);
+        return Bracket::new(vec![
+            first,
+            set(2, 1, vec![slot(None, Some(200)), slot(None, Some(300))]),
+            set(3, 2, vec![slot(Some((1, 1)), Some(100)), slot(Some((2, 1)), None)]),
+            set(4, -1, vec![slot(Some((1, 2)), Some(400)), slot(Some((2, 2)), None)]),
+            set(5, -2, vec![slot(Some((3, 2)), None), slot(Some((4, 1)), None)]),
+            set(6, 3, vec![slot(Some((3, 1)), None), slot(Some((5, 1)), None)]),
+            set(7, 4, vec![slot(Some((6, 1)), None), slot(Some((6, 2)), None)]),
+        ]);
+    }
+
+    fn ids(sets: &[&GGSet]) -> Vec<String> {
+        return sets.iter().map(|set| set.id().to_string()).collect();
+    }
+
+    #[test]
+    fn double_elimination_progression() {
+        let bracket = double_elimination();
+        assert!(bracket.is_double_elimination());
+        assert_eq!(bracket.loser_destination(&GGID::Int(1)).unwrap().id().to_string(), "4");
+        assert_eq!(bracket.loser_destination(&GGID::Int(3)).unwrap().id().to_string(), "5");
+        assert!(bracket.loser_destination(&GGID::Int(4)).is_none());
+        assert_eq!(bracket.grand_finals().unwrap().id().to_string(), "6");
+        assert_eq!(bracket.grand_finals_reset().unwrap().id().to_string(), "7");
+        assert_eq!(ids(&bracket.path_to_grand_finals(&GGID::Int(4))), vec!["4", "5", "6"]);
+    }
+
+    #[test]
+    fn remaining_sets_follow_results() {
+        let bracket = double_elimination();
+        assert_eq!(ids(&bracket.remaining_sets(&GGID::Int(100))), vec!["3", "5", "6", "7"]);
+        assert_eq!(ids(&bracket.remaining_sets(&GGID::Int(400))), vec!["4", "5", "6", "7"]);
+    }
+
+    #[test]
+    fn single_elimination_progression() {
+        let bracket = Bracket::new(vec![
+            set(1, 1, vec![slot(None, Some(100)), slot(None, Some(400))]),
+            set(2, 1, vec![slot(None, Some(200)), slot(None, Some(300))]),
+            set(3, 2, vec![slot(Some((1, 1)), None), slot(Some((2, 1)), None)]),
+        ]);
+        assert!(!bracket.is_double_elimination());
+        assert!(bracket.loser_destination(&GGID::Int(1)).is_none());
+        assert!(bracket.grand_finals_reset().is_none());
+        assert_eq!(bracket.grand_finals().unwrap().id().to_string(), "3");
+        assert_eq!(ids(&bracket.remaining_sets(&GGID::Int(200))), vec!["2", "3"]);
+    }
+}
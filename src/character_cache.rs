@@ -0,0 +1,102 @@
+//! A cache of videogame characters, used to resolve character ids without another query.
+
+use std::collections::HashMap;
+
+use crate::{
+    enums::*,
+    get_videogame,
+    structs::*,
+};
+
+/// Character table for each videogame.
+///
+/// Each videogame's characters are only fetched once, after which any character id (such as the selection value of a game selection) can be resolved to a name and images.
+#[derive(Clone, Default)]
+pub struct CharacterCache {
+
+    videogames: HashMap<i64, HashMap<i64, GGCharacter>>,
+
+}
+
+impl CharacterCache {
+
+    /// Creates an empty cache.
+    pub fn new() -> CharacterCache {
+        return Default::default();
+    }
+
+    /// Fetches the characters of a videogame if they aren't already cached.
+    ///
+    /// Returns the error message if the query failed or the videogame wasn't found.
+    pub async fn load(&mut self, videogame_id: i64, token: &str) -> Result<(), String> {
+        if self.videogames.contains_key(&videogame_id) {
+            return Ok(());
+        }
+        match get_videogame(GGID::Int(videogame_id), token).await {
+            GGResponse::Data(data) => match &data.videogame {
+                Some(videogame) => {
+                    self.insert_characters(videogame_id, videogame);
+                    return Ok(());
+                },
+                None => return Err(format!("Videogame {} was not found", videogame_id)),
+            },
+            GGResponse::Error(e) => return Err(e),
+        }
+    }
+
+    /// Adds the characters of an already queried videogame to the cache.
+    pub fn insert(&mut self, videogame: &GGVideogame) {
+        self.insert_characters(videogame.id(), videogame);
+    }
+
+    /// Adds the characters of a videogame to the cache under the given id.
+    fn insert_characters(&mut self, videogame_id: i64, videogame: &GGVideogame) {
+        let mut characters: HashMap<i64, GGCharacter> = HashMap::new();
+        for character in videogame.characters() {
+            characters.insert(character.id(), character);
+        }
+        self.videogames.insert(videogame_id, characters);
+    }
+
+    /// Returns the character table of a videogame.
+    ///
+    /// Returns an empty table if the videogame hasn't been loaded.
+    pub fn characters(&self, videogame_id: i64) -> HashMap<i64, GGCharacter> {
+        let mut result: HashMap<i64, GGCharacter> = HashMap::new();
+        if self.videogames.contains_key(&videogame_id) {
+            result = self.videogames[&videogame_id].clone();
+        }
+        return result;
+    }
+
+    /// Returns a character by id from any loaded videogame.
+    ///
+    /// Returns None if the character isn't in the cache.
+    pub fn character(&self, id: i64) -> Option<GGCharacter> {
+        for characters in self.videogames.values() {
+            if let Some(character) = characters.get(&id) {
+                return Some(character.clone());
+            }
+        }
+        return None;
+    }
+
+    /// Returns the name of a character by id.
+    ///
+    /// Returns an empty string if the character isn't in the cache.
+    pub fn character_name(&self, id: i64) -> String {
+        let mut result: String = "".to_string();
+        if let Some(character) = self.character(id) {
+            result = character.name();
+        }
+        return result;
+    }
+
+    /// Returns the character picked in a game selection.
+    ///
+    /// Returns None if the selection's value isn't a character in the cache.
+    pub fn selection_character(&self, selection: &GGGameSelection) -> Option<GGCharacter> {
+        return self.character(selection.selection_value());
+    }
+
+}
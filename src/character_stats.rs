@@ -0,0 +1,378 @@
+//! Character and stage usage statistics, computed from the selections and stages of each game.
+//!
+//! Statistics are gathered for every entrant in the given sets, or only for one player's entrants.
+//! Character ids are resolved to names with a CharacterCache.
+
+use std::collections::HashMap;
+
+use crate::{
+    character_cache::*,
+    enums::*,
+    execute_paginated_query,
+    structs::*,
+    Vars,
+};
+
+/// Whose games statistics are gathered for.
+#[derive(Clone, PartialEq, Eq)]
+pub enum StatsScope {
+
+    /// Every entrant in the sets.
+    All,
+
+    /// The entrants of a player, by player id.
+    Player(i64),
+
+}
+
+/// How often a character or stage was picked, and how often it won.
+#[derive(Clone)]
+pub struct UsageRecord {
+
+    pub id:             i64,
+    pub name:           String,
+    pub picks:          i64,
+    pub wins:           i64,
+
+    /// The share of picks out of every pick of the same kind.
+    pub pick_rate:      f64,
+
+    /// The share of picks that won the game.
+    pub win_rate:       f64,
+
+}
+
+/// The win rate of each character against each other character.
+#[derive(Clone, Default)]
+pub struct MatchupMatrix {
+
+    /// The characters of the rows and columns, by id and name.
+    pub characters:     Vec<(i64, String)>,
+
+    /// The win rate of the row character against the column character, or None if they never played each other.
+    pub win_rates:      Vec<Vec<Option<f64>>>,
+
+}
+
+/// Picks and wins, counted together.
+#[derive(Clone, Copy, Default)]
+struct Count {
+
+    picks:  i64,
+    wins:   i64,
+
+}
+
+/// Character, stage and matchup statistics for a set of games.
+#[derive(Clone)]
+pub struct CharacterStats {
+
+    scope:              StatsScope,
+    names:              HashMap<i64, String>,
+    stage_names:        HashMap<i64, String>,
+    characters:         HashMap<i64, Count>,
+    stages:             HashMap<i64, Count>,
+    character_stages:   HashMap<(i64, i64), Count>,
+
+    /// Games of the first character against the second, and the first character's wins.
+    matchups:           HashMap<(i64, i64), Count>,
+
+}
+
+impl CharacterStats {
+
+    /// Creates empty statistics for a scope.
+    pub fn new(scope: StatsScope) -> CharacterStats {
+        return CharacterStats {
+            scope:              scope,
+            names:              HashMap::new(),
+            stage_names:        HashMap::new(),
+            characters:         HashMap::new(),
+            stages:             HashMap::new(),
+            character_stages:   HashMap::new(),
+            matchups:           HashMap::new(),
+        };
+    }
+
+    /// Fetches the games of an event and gathers their statistics.
+    ///
+    /// The event's characters are loaded into the cache if they aren't already. Returns the error message if a query failed.
+    pub async fn load_event(event_id: GGID, scope: StatsScope, cache: &mut CharacterCache, token: &str) -> Result<CharacterStats, String> {
+
+        let query = r#"
+        query EventGames($id: ID!, $page: Int!, $per_page: Int!) {
+            event(id: $id) {
+                id
+                videogame {
+                    id
+                }
+                sets(page: $page, perPage: $per_page, sortType: STANDARD) {
+                    pageInfo {
+                        totalPages
+                    }
+                    nodes {
+                        id
+                        slots {
+                            entrant {
+                                id
+                                participants {
+                                    id
+                                    player {
+                                        id
+                                    }
+                                }
+                            }
+                        }
+                        games {
+                            id
+                            winnerId
+                            stage {
+                                id
+                                name
+                            }
+                            selections {
+                                selectionType
+                                selectionValue
+                                entrant {
+                                    id
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        "#;
+
+        let vars = |page| Vars { id: event_id.clone(), slug: "".to_string(), page: page, per_page: 20 };
+
+        let response = execute_paginated_query(token, query, vars, |data| {
+            let sets = data.event.as_mut()?.sets.as_mut()?;
+            Some((sets.page_info().total_pages(), &mut sets.nodes))
+        }).await;
+        let event = match response {
+            GGResponse::Data(data) => data.event(),
+            GGResponse::Error(e) => return Err(e),
+        };
+        cache.load(event.videogame().id(), token).await?;
+
+        let mut stats = CharacterStats::new(scope);
+        stats.add_sets(&event.sets(), cache);
+        return Ok(stats);
+    }
+
+    /// Adds the games of sets to the statistics.
+    ///
+    /// Each game needs its winner, stage and selections, with the entrant, type and value of each selection.
+    /// When scoped to a player, the set's slots also need their entrant, participants and player.
+    pub fn add_sets(&mut self, sets: &[GGSet], cache: &CharacterCache) {
+        for set in sets {
+            let tracked: Vec<String> = set.slots().iter()
+                .filter(|slot| slot.entrant.is_some())
+                .map(|slot| slot.entrant())
+                .filter(|entrant| match self.scope {
+                    StatsScope::All => true,
+                    StatsScope::Player(id) => entrant.participants().iter().any(|participant| participant.player().id() == id),
+                })
+                .map(|entrant| entrant.id().to_string())
+                .collect();
+            for game in set.games() {
+                self.add_game(&game, &tracked, cache);
+            }
+        }
+    }
+
+    /// Adds a game, counting the picks of the tracked entrants.
+    fn add_game(&mut self, game: &GGGame, tracked: &[String], cache: &CharacterCache) {
+        // Each entrant's characters in the game, in the order of the selections.
+        let mut sides: Vec<(String, Vec<i64>)> = Vec::new();
+        for selection in game.selections() {
+            let is_character = selection.selection_type.is_none_or(|value| value == GGGameSelectionType::Character as i64);
+            if !is_character || selection.selection_value.is_none() {
+                continue;
+            }
+            let entrant_id = selection.entrant().id().to_string();
+            match sides.iter_mut().find(|(id, _)| *id == entrant_id) {
+                Some((_, characters)) => characters.push(selection.selection_value()),
+                None => sides.push((entrant_id, vec![selection.selection_value()])),
+            }
+            self.names.entry(selection.selection_value()).or_insert_with(|| cache.character_name(selection.selection_value()));
+        }
+
+        let winner_id = game.winner_id().to_string();
+        let stage = game.stage.as_ref().map(|stage| (stage.id(), stage.name()));
+        let mut stage_counted = false;
+        for (entrant_id, characters) in &sides {
+            if !tracked.contains(entrant_id) {
+                continue;
+            }
+            let won = game.winner_id.is_some() && *entrant_id == winner_id;
+            for character in characters {
+                add(self.characters.entry(*character).or_default(), won);
+                if let Some((stage_id, _)) = &stage {
+                    add(self.character_stages.entry((*character, *stage_id)).or_default(), won);
+                }
+            }
+            if let Some((stage_id, stage_name)) = &stage {
+                self.stage_names.insert(*stage_id, stage_name.clone());
+                // Stages are counted once per game when every entrant is tracked, and once per tracked entrant otherwise.
+                if self.scope != StatsScope::All || !stage_counted {
+                    add(self.stages.entry(*stage_id).or_default(), won);
+                    stage_counted = true;
+                }
+            }
+            // Matchups are only counted for singles games, with one character on each side.
+            if sides.len() == 2 && characters.len() == 1 {
+                let opponent = sides.iter().find(|(id, _)| id != entrant_id).unwrap();
+                if opponent.1.len() == 1 && game.winner_id.is_some() {
+                    add(self.matchups.entry((characters[0], opponent.1[0])).or_default(), won);
+                }
+            }
+        }
+    }
+
+    /// Returns the usage of every character, from most to least picked.
+    pub fn characters(&self) -> Vec<UsageRecord> {
+        return records(&self.characters, &self.names);
+    }
+
+    /// Returns the usage of every stage, from most to least picked.
+    ///
+    /// When every entrant is tracked each game is one pick, so the win rate is only meaningful when scoped to a player.
+    /// See character_stages() for how each character does on each stage.
+    pub fn stages(&self) -> Vec<UsageRecord> {
+        return records(&self.stages, &self.stage_names);
+    }
+
+    /// Returns the usage of each stage by a character, from most to least picked.
+    ///
+    /// The pick rate is the share of the character's games played on the stage.
+    pub fn character_stages(&self, character_id: i64) -> Vec<UsageRecord> {
+        let counts: HashMap<i64, Count> = self.character_stages.iter()
+            .filter(|((character, _), _)| *character == character_id)
+            .map(|((_, stage), count)| (*stage, *count))
+            .collect();
+        return records(&counts, &self.stage_names);
+    }
+
+    /// Returns the games played between two characters and the first character's wins.
+    pub fn matchup(&self, character_id: i64, opponent_id: i64) -> (i64, i64) {
+        let count = self.matchups.get(&(character_id, opponent_id)).cloned().unwrap_or_default();
+        return (count.wins, count.picks);
+    }
+
+    /// Returns the matchup matrix of every character with a matchup, ordered by number of picks.
+    pub fn matchup_matrix(&self) -> MatchupMatrix {
+        let characters: Vec<(i64, String)> = self.characters().into_iter()
+            .filter(|record| self.matchups.keys().any(|(a, b)| *a == record.id || *b == record.id))
+            .map(|record| (record.id, record.name))
+            .collect();
+        let win_rates = characters.iter().map(|(row, _)| {
+            characters.iter().map(|(column, _)| {
+                let (wins, games) = self.matchup(*row, *column);
+                if games == 0 { None } else { Some(wins as f64 / games as f64) }
+            }).collect()
+        }).collect();
+        return MatchupMatrix { characters: characters, win_rates: win_rates };
+    }
+
+}
+
+/// Counts a pick, and a win if it won.
+fn add(count: &mut Count, won: bool) {
+    count.picks += 1;
+    if won {
+        count.wins += 1;
+    }
+}
+
+/// Returns the usage records of counts, from most to least picked.
+fn records(counts: &HashMap<i64, Count>, names: &HashMap<i64, String>) -> Vec<UsageRecord> {
+    let total: i64 = counts.values().map(|count| count.picks).sum();
+    let mut result: Vec<UsageRecord> = counts.iter().map(|(id, count)| UsageRecord {
+        id:         *id,
+        name:       names.get(id).cloned().unwrap_or_default(),
+        picks:      count.picks,
+        wins:       count.wins,
+        pick_rate:  count.picks as f64 / total as f64,
+        win_rate:   count.wins as f64 / count.picks as f64,
+    }).collect();
+    result.sort_by_key(|record| (-record.picks, record.id));
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> CharacterCache {
+        let mut cache = CharacterCache::new();
+        let character = |id: i64, name: &str| GGCharacter { id: Some(id), name: Some(name.to_string()), ..Default::default() };
+        cache.insert(&GGVideogame { id: Some(1), characters: Some(vec![character(1, "Fox"), character(2, "Marth"), character(3, "Sheik")]), ..Default::default() });
+        return cache;
+    }
+
+    fn game(winner: i64, stage: i64, characters: (i64, i64)) -> GGGame {
+        let selection = |entrant: i64, character: i64| GGGameSelection {
+            entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(entrant)), ..Default::default() })),
+            selection_type: Some(GGGameSelectionType::Character as i64),
+            selection_value: Some(character),
+            ..Default::default()
+        };
+        return GGGame {
+            winner_id: Some(winner),
+            stage: Some(Box::new(GGStage { id: Some(stage), name: Some(format!("Stage {}", stage)) })),
+            selections: Some(vec![selection(10, characters.0), selection(20, characters.1)]),
+            ..Default::default()
+        };
+    }
+
+    fn sets() -> Vec<GGSet> {
+        let slot = |entrant: i64, player: i64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant {
+                id: Some(GGID::Int(entrant)),
+                participants: Some(vec![GGParticipant { player: Some(Box::new(GGPlayer { id: Some(player), ..Default::default() })), ..Default::default() }]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        return vec![GGSet {
+            slots: Some(vec![slot(10, 1), slot(20, 2)]),
+            games: Some(vec![game(10, 100, (1, 2)), game(20, 200, (1, 2)), game(10, 100, (1, 3))]),
+            ..Default::default()
+        }];
+    }
+
+    #[test]
+    fn event_statistics() {
+        let mut stats = CharacterStats::new(StatsScope::All);
+        stats.add_sets(&sets(), &cache());
+
+        let characters: Vec<(String, i64, i64)> = stats.characters().iter().map(|record| (record.name.clone(), record.picks, record.wins)).collect();
+        assert_eq!(characters, vec![("Fox".to_string(), 3, 2), ("Marth".to_string(), 2, 1), ("Sheik".to_string(), 1, 0)]);
+        assert_eq!(stats.characters()[0].pick_rate, 0.5);
+
+        let stages: Vec<(String, i64)> = stats.stages().iter().map(|record| (record.name.clone(), record.picks)).collect();
+        assert_eq!(stages, vec![("Stage 100".to_string(), 2), ("Stage 200".to_string(), 1)]);
+        assert_eq!(stats.character_stages(1)[0].win_rate, 1.0);
+
+        assert_eq!(stats.matchup(1, 2), (1, 2));
+        assert_eq!(stats.matchup(2, 1), (1, 2));
+        let matrix = stats.matchup_matrix();
+        assert_eq!(matrix.characters.len(), 3);
+        assert!(matrix.win_rates[0] == vec![None, Some(0.5), Some(1.0)]);
+    }
+
+    #[test]
+    fn player_statistics() {
+        let mut stats = CharacterStats::new(StatsScope::Player(2));
+        stats.add_sets(&sets(), &cache());
+
+        let characters: Vec<(i64, i64, i64)> = stats.characters().iter().map(|record| (record.id, record.picks, record.wins)).collect();
+        assert_eq!(characters, vec![(2, 2, 1), (3, 1, 0)]);
+        let stages: Vec<(i64, f64)> = stats.stages().iter().map(|record| (record.id, record.win_rate)).collect();
+        assert_eq!(stages, vec![(100, 0.0), (200, 1.0)]);
+        assert_eq!(stats.matchup(3, 1), (0, 1));
+        assert_eq!(stats.matchup(1, 3), (0, 0));
+    }
+}
@@ -0,0 +1,287 @@
+//! Dry runs of mutations, used to check a change to a set before it is made.
+//!
+//! A dry run fetches the current state of the set, validates the change locally and returns what would change, without sending the mutation.
+
+use crate::{
+    enums::*,
+    execute_query,
+    inputs::*,
+    mutations::*,
+    structs::*,
+    Vars,
+};
+
+/// A change to a set that can be checked with a dry run before being made.
+#[derive(Clone)]
+pub enum SetChange {
+
+    /// Report the set, see report_bracket_set().
+    Report {
+        winner_id: GGID,
+        is_dq: bool,
+        game_data: Vec<BracketSetGameDataInput>,
+    },
+
+    /// Update the games of the set without completing it, see update_bracket_set().
+    Update {
+        game_data: Vec<BracketSetGameDataInput>,
+    },
+
+    /// Mark the set as called, see mark_set_called().
+    MarkCalled,
+
+    /// Mark the set as in progress, see mark_set_in_progress().
+    MarkInProgress,
+
+    /// Reset the set, see reset_set().
+    Reset {
+        reset_dependent_sets: bool,
+    },
+
+    /// Assign the set to a station, see assign_station().
+    AssignStation {
+        station_id: GGID,
+    },
+
+    /// Assign the set to a stream, see assign_stream().
+    AssignStream {
+        stream_id: GGID,
+    },
+
+}
+
+impl SetChange {
+
+    /// Makes the change by sending the mutation.
+    pub async fn execute(self, set_id: GGID, token: &str) -> GGResponse {
+        match self {
+            SetChange::Report { winner_id, is_dq, game_data } => return report_bracket_set(set_id, winner_id, is_dq, game_data, token).await,
+            SetChange::Update { game_data } => return update_bracket_set(set_id, game_data, token).await,
+            SetChange::MarkCalled => return mark_set_called(set_id, token).await,
+            SetChange::MarkInProgress => return mark_set_in_progress(set_id, token).await,
+            SetChange::Reset { reset_dependent_sets } => return reset_set(set_id, reset_dependent_sets, token).await,
+            SetChange::AssignStation { station_id } => return assign_station(set_id, station_id, token).await,
+            SetChange::AssignStream { stream_id } => return assign_stream(set_id, stream_id, token).await,
+        }
+    }
+
+}
+
+/// A single value of a set that would change.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FieldChange {
+
+    pub field:      String,
+    pub before:     String,
+    pub after:      String,
+
+}
+
+/// Everything that would change in a set.
+#[derive(Clone)]
+pub struct SetDiff {
+
+    pub set_id:     GGID,
+    pub changes:    Vec<FieldChange>,
+
+}
+
+impl SetDiff {
+
+    /// Returns if the change wouldn't change anything.
+    pub fn is_empty(&self) -> bool {
+        return self.changes.is_empty();
+    }
+
+    fn push(&mut self, field: &str, before: String, after: String) {
+        if before != after {
+            self.changes.push(FieldChange { field: field.to_string(), before: before, after: after });
+        }
+    }
+
+}
+
+/// Returns a readable name for the state of a set.
+fn state_name(state: i64) -> String {
+    match GGActivityState::try_from(state) {
+        Ok(GGActivityState::Created) => return "created".to_string(),
+        Ok(GGActivityState::Active) => return "in progress".to_string(),
+        Ok(GGActivityState::Completed) => return "completed".to_string(),
+        Ok(GGActivityState::Ready) => return "ready".to_string(),
+        Ok(GGActivityState::Invalid) => return "invalid".to_string(),
+        Ok(GGActivityState::Called) => return "called".to_string(),
+        Ok(GGActivityState::Queued) => return "queued".to_string(),
+        Err(_) => return state.to_string(),
+    }
+}
+
+/// Returns an error if an entrant isn't in one of the set's slots.
+fn check_entrant(set: &GGSet, entrant_id: &GGID) -> Result<(), String> {
+    if set.slots().iter().any(|slot| slot.entrant.is_some() && slot.entrant().id().to_string() == entrant_id.to_string()) {
+        return Ok(());
+    }
+    return Err(format!("Entrant {} is not in set {}", entrant_id, set.id()));
+}
+
+/// Returns an error if the games reference entrants that aren't in the set.
+fn check_game_data(set: &GGSet, game_data: &[BracketSetGameDataInput]) -> Result<(), String> {
+    for game in game_data {
+        if let Some(winner_id) = &game.winner_id {
+            check_entrant(set, winner_id)?;
+        }
+        for selection in game.selections.iter().flatten() {
+            check_entrant(set, &selection.entrant_id)?;
+        }
+    }
+    return Ok(());
+}
+
+/// Validates a change against the current state of a set.
+///
+/// The winner and any entrants in the game data must be in the set's slots, and a completed set can only be reset.
+/// Returns what would change, or an error describing why the change is invalid.
+pub fn validate_set_change(set: &GGSet, change: &SetChange) -> Result<SetDiff, String> {
+    let completed = set.state() == GGActivityState::Completed as i64;
+    let is_reset = matches!(change, SetChange::Reset { .. });
+    if completed && !is_reset {
+        return Err(format!("Set {} is already completed and can only be reset", set.id()));
+    }
+
+    let mut diff = SetDiff { set_id: set.id(), changes: Vec::new() };
+    let winner = if set.winner_id.is_some() { set.winner_id().to_string() } else { "".to_string() };
+    match change {
+        SetChange::Report { winner_id, is_dq, game_data } => {
+            check_entrant(set, winner_id)?;
+            check_game_data(set, game_data)?;
+            diff.push("state", state_name(set.state()), state_name(GGActivityState::Completed as i64));
+            diff.push("winnerId", winner, winner_id.to_string());
+            if *is_dq {
+                diff.push("displayScore", set.display_score(), "DQ".to_string());
+            } else if !game_data.is_empty() {
+                diff.push("games", set.games().len().to_string(), game_data.len().to_string());
+            }
+        },
+        SetChange::Update { game_data } => {
+            check_game_data(set, game_data)?;
+            diff.push("games", set.games().len().to_string(), game_data.len().to_string());
+        },
+        SetChange::MarkCalled => {
+            diff.push("state", state_name(set.state()), state_name(GGActivityState::Called as i64));
+        },
+        SetChange::MarkInProgress => {
+            diff.push("state", state_name(set.state()), state_name(GGActivityState::Active as i64));
+        },
+        SetChange::Reset { reset_dependent_sets: _ } => {
+            diff.push("state", state_name(set.state()), state_name(GGActivityState::Created as i64));
+            diff.push("winnerId", winner, "".to_string());
+            diff.push("displayScore", set.display_score(), "".to_string());
+        },
+        SetChange::AssignStation { station_id } => {
+            let station = if set.station.is_some() { set.station().id().to_string() } else { "".to_string() };
+            diff.push("station", station, station_id.to_string());
+        },
+        SetChange::AssignStream { stream_id } => {
+            let stream = if set.stream.is_some() { set.stream().id().to_string() } else { "".to_string() };
+            diff.push("stream", stream, stream_id.to_string());
+        },
+    }
+    return Ok(diff);
+}
+
+/// Dry run a change to a set.
+///
+/// Fetches the set's slots, state, winner, station and stream, then validates the change without sending the mutation.
+/// Returns what would change, or an error if the set couldn't be fetched or the change is invalid.
+pub async fn dry_run_set_change(
+    set_id: GGID,
+    change: &SetChange,
+    token: &str,
+) -> Result<SetDiff, String> {
+
+    let query = r#"
+    query SetState($id: ID!) {
+        set(id: $id) {
+            id
+            state
+            winnerId
+            displayScore
+            games {
+                id
+            }
+            slots {
+                entrant {
+                    id
+                    name
+                }
+            }
+            station {
+                id
+            }
+            stream {
+                id
+            }
+        }
+    }
+    "#;
+
+    let vars = Vars { id: set_id, slug: "".to_string(), page: 1, per_page: 100 };
+
+    match execute_query(token, query, vars).await {
+        GGResponse::Data(data) => {
+            if data.set.is_none() {
+                return Err("The set could not be found".to_string());
+            }
+            return validate_set_change(&data.set(), change);
+        },
+        GGResponse::Error(e) => return Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(state: GGActivityState, winner_id: Option<i64>) -> GGSet {
+        let slot = |id: i64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(id)), ..Default::default() })),
+            ..Default::default()
+        };
+        GGSet {
+            id: Some(GGID::Int(100)),
+            state: Some(state as i64),
+            winner_id: winner_id,
+            slots: Some(vec![slot(1), slot(2)]),
+            ..Default::default()
+        }
+    }
+
+    fn report(winner_id: i64) -> SetChange {
+        SetChange::Report { winner_id: GGID::Int(winner_id), is_dq: false, game_data: Vec::new() }
+    }
+
+    #[test]
+    fn report_requires_a_slot_entrant() {
+        let diff = validate_set_change(&set(GGActivityState::Active, None), &report(2)).ok().unwrap();
+        let fields: Vec<String> = diff.changes.iter().map(|change| format!("{}: {} -> {}", change.field, change.before, change.after)).collect();
+        assert_eq!(fields, vec!["state: in progress -> completed", "winnerId:  -> 2"]);
+
+        assert!(validate_set_change(&set(GGActivityState::Active, None), &report(3)).is_err());
+    }
+
+    #[test]
+    fn completed_sets_can_only_be_reset() {
+        let completed = set(GGActivityState::Completed, Some(1));
+        assert!(validate_set_change(&completed, &report(2)).is_err());
+        assert!(validate_set_change(&completed, &SetChange::MarkCalled).is_err());
+
+        let diff = validate_set_change(&completed, &SetChange::Reset { reset_dependent_sets: true }).ok().unwrap();
+        assert_eq!(diff.changes.len(), 2);
+    }
+
+    #[test]
+    fn game_data_entrants_are_checked() {
+        let game = BracketSetGameDataInput { winner_id: Some(GGID::Int(5)), game_num: 1, ..Default::default() };
+        let change = SetChange::Update { game_data: vec![game] };
+        assert!(validate_set_change(&set(GGActivityState::Active, None), &change).is_err());
+    }
+}
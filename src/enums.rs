@@ -3,19 +3,27 @@
 //!
 //! These enums are used to either aid in serializing / deserializing or are representations of existing enums in the start.gg API.
 
+use chrono::{
+    DateTime,
+    Utc,
+};
 use serde::{
     Deserialize,
+    Deserializer,
     Serialize,
 };
 
-use std::str::FromStr;
+use std::{
+    fmt,
+    str::FromStr,
+};
 
 use crate::structs::query::*;
 
 /// Equivalent for start.gg ID.
 ///
 /// An ID is either a String or an i64, which is usually an i64.
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(untagged)]
 pub enum GGID {
     
@@ -35,9 +43,20 @@ impl FromStr for GGID {
     }
 }
 
+impl fmt::Display for GGID {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GGID::Int(value) => write!(f, "{}", value),
+            GGID::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
 /// Enumeration to catch errors.
 ///
 /// This enum will either be a proper response  or just a string containing an error.
+// Boxing the data would change how every response is matched on, and a response is only moved a few times.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GGResponse {
@@ -46,3 +65,173 @@ pub enum GGResponse {
     Error(String),
 
 }
+
+/// Equivalent for start.gg ActivityState.
+///
+/// The state of a set, phase group, or other activity, which start.gg returns as a number.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum GGActivityState {
+
+    Created = 1,
+    Active = 2,
+    Completed = 3,
+    Ready = 4,
+    Invalid = 5,
+    Called = 6,
+    Queued = 7,
+
+}
+
+impl TryFrom<i64> for GGActivityState {
+    type Error = ();
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => GGActivityState::Created,
+            2 => GGActivityState::Active,
+            3 => GGActivityState::Completed,
+            4 => GGActivityState::Ready,
+            5 => GGActivityState::Invalid,
+            6 => GGActivityState::Called,
+            7 => GGActivityState::Queued,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Equivalent for start.gg BracketType.
+///
+/// The format of a phase or phase group. start.gg returns the name of the type, which the structures store as this number.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GGBracketType {
+
+    SingleElimination = 1,
+    DoubleElimination = 2,
+    RoundRobin = 3,
+    Swiss = 4,
+    Exhibition = 5,
+    CustomSchedule = 6,
+    Matchmaking = 7,
+    EliminationRounds = 8,
+    Race = 9,
+    Circuit = 10,
+
+}
+
+impl TryFrom<i64> for GGBracketType {
+    type Error = ();
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => GGBracketType::SingleElimination,
+            2 => GGBracketType::DoubleElimination,
+            3 => GGBracketType::RoundRobin,
+            4 => GGBracketType::Swiss,
+            5 => GGBracketType::Exhibition,
+            6 => GGBracketType::CustomSchedule,
+            7 => GGBracketType::Matchmaking,
+            8 => GGBracketType::EliminationRounds,
+            9 => GGBracketType::Race,
+            10 => GGBracketType::Circuit,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Equivalent for start.gg GameSelectionType.
+///
+/// What a game selection's value is. start.gg can return the name of the type, which the structures store as this number.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GGGameSelectionType {
+
+    Character = 1,
+
+}
+
+impl TryFrom<i64> for GGGameSelectionType {
+    type Error = ();
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => GGGameSelectionType::Character,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// An enum value returned either as its number or its name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrName<T> {
+
+    Number(i64),
+    Name(T),
+
+}
+
+/// Deserializes an enum value given either as a number or by name into its number.
+fn deserialize_number_or_name<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D, number: fn(T) -> i64) -> Result<Option<i64>, D::Error> {
+    match Option::<NumberOrName<T>>::deserialize(deserializer)? {
+        Some(NumberOrName::Number(value)) => return Ok(Some(value)),
+        Some(NumberOrName::Name(value)) => return Ok(Some(number(value))),
+        None => return Ok(None),
+    }
+}
+
+/// Deserializes a bracket type given either as a number or by name into its number.
+pub(crate) fn deserialize_bracket_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    return deserialize_number_or_name(deserializer, |value: GGBracketType| value as i64);
+}
+
+/// Deserializes a game selection type given either as a number or by name into its number.
+pub(crate) fn deserialize_game_selection_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    return deserialize_number_or_name(deserializer, |value: GGGameSelectionType| value as i64);
+}
+
+/// Enumeration of errors from registering for a tournament.
+///
+/// Registration is checked before anything is sent, so a closed registration window is caught without making the mutation.
+#[derive(Clone, PartialEq, Eq)]
+pub enum GGRegistrationError {
+
+    /// Registration isn't open, or closed at the given time.
+    ///
+    /// The time is None if the tournament doesn't have a time registration closes at.
+    RegistrationClosed(Option<DateTime<Utc>>),
+
+    /// Any other error returned while registering.
+    Error(String),
+
+}
+
+/// Equivalent for start.gg SetSortType.
+///
+/// The order sets are returned in, see get_phase_group_sets().
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GGSetSortType {
+
+    None,
+    Standard,
+    Recent,
+    CallOrder,
+    Magic,
+    Round,
+
+}
+
+/// Equivalent for start.gg TournamentPaginationSort.
+///
+/// The value used to sort tournaments when searching.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GGTournamentPaginationSort {
+
+    StartAt,
+    EndAt,
+    EventRegistrationClosesAt,
+    ComputedUpdatedAt,
+
+}
@@ -0,0 +1,272 @@
+//! Head to head records between two players across every event they have met in.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use std::collections::HashSet;
+
+use crate::{
+    enums::*,
+    execute_paginated_query,
+    inputs::*,
+    structs::*,
+    PlayerVars,
+};
+
+/// A set two players played against each other.
+#[derive(Clone)]
+pub struct Meeting {
+
+    pub set:            GGSet,
+    pub event:          GGEvent,
+    pub completed_at:   DateTime<Utc>,
+
+    /// If the first player won the set.
+    pub won:            bool,
+
+    /// The games won by the first player, then the second.
+    pub games:          (i64, i64),
+
+}
+
+/// The record between two players, from the point of view of the first.
+#[derive(Clone, Default)]
+pub struct HeadToHeadRecord {
+
+    pub player_id:          i64,
+    pub opponent_id:        i64,
+    pub set_wins:           i64,
+    pub set_losses:         i64,
+    pub game_wins:          i64,
+    pub game_losses:        i64,
+
+    /// Every set the players played against each other, from oldest to most recent.
+    pub meetings:           Vec<Meeting>,
+
+}
+
+impl HeadToHeadRecord {
+
+    /// Computes the record between two players from sets either of them played.
+    ///
+    /// Each set is matched on the players of its entrants' participants, so the set's slots need their entrant, participants and player.
+    /// Sets that don't pass the filter, weren't completed, or were listed more than once are skipped.
+    pub fn new(player_id: i64, opponent_id: i64, sets: &[GGSet], filter: &SetHistoryFilter) -> HeadToHeadRecord {
+        let mut record = HeadToHeadRecord { player_id: player_id, opponent_id: opponent_id, ..Default::default() };
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for set in sets {
+            if set.state.is_some() && set.state() != GGActivityState::Completed as i64 {
+                continue;
+            }
+            if set.winner_id.is_none() || !filter.matches(set) || !seen.insert(set.id().to_string()) {
+                continue;
+            }
+            let slots = set.slots();
+            // Participants without a player are never matched, rather than being taken as player zero.
+            let has_player = |slot: &GGSetSlot, id: i64| slot.entrant().participants().iter()
+                .any(|participant| participant.player().id.is_some() && participant.player().id() == id);
+            let player_slot = slots.iter().position(|slot| has_player(slot, player_id));
+            let opponent_slot = slots.iter().position(|slot| has_player(slot, opponent_id));
+            let (player_slot, opponent_slot) = match (player_slot, opponent_slot) {
+                (Some(player_slot), Some(opponent_slot)) if player_slot != opponent_slot => (&slots[player_slot], &slots[opponent_slot]),
+                _ => continue,
+            };
+
+            let won = player_slot.entrant().id().to_string() == set.winner_id().to_string();
+            let games = if set.display_score() == "DQ" { (0, 0) } else { (slot_games(set, player_slot), slot_games(set, opponent_slot)) };
+            if won {
+                record.set_wins += 1;
+            } else {
+                record.set_losses += 1;
+            }
+            record.game_wins += games.0;
+            record.game_losses += games.1;
+            record.meetings.push(Meeting { set: set.clone(), event: set.event(), completed_at: set.completed_at(), won: won, games: games });
+        }
+
+        record.meetings.sort_by_key(|meeting| meeting.completed_at);
+        return record;
+    }
+
+    /// Returns the most recent set the players played against each other.
+    ///
+    /// Returns None if they haven't played each other.
+    pub fn most_recent(&self) -> Option<&Meeting> {
+        return self.meetings.last();
+    }
+
+}
+
+/// Returns the games won by the entrant in a slot of a set.
+///
+/// Uses the slot's score when it was queried, otherwise counts the games won by the entrant.
+fn slot_games(set: &GGSet, slot: &GGSetSlot) -> i64 {
+    if let Some(value) = slot.standing.as_ref().and_then(|standing| standing.stats().score().value) {
+        return value.max(0.0) as i64;
+    }
+    let entrant_id = slot.entrant().id().to_string();
+    return set.games().iter().filter(|game| game.winner_id().to_string() == entrant_id).count() as i64;
+}
+
+/// Get the head to head record between two players.
+///
+/// Fetches the set history of both players and matches the sets on the players of each entrant's participants.
+/// Both histories are fetched because a set only appears in a player's history when their entrant is linked to their player, which isn't always the case for both entrants.
+/// Returns the record from the point of view of the first player, or the error message if a player id isn't a number or a query failed.
+pub async fn head_to_head(
+    player_a: GGID,
+    player_b: GGID,
+    filter: SetHistoryFilter,
+    token: &str,
+) -> Result<HeadToHeadRecord, String> {
+
+    let query = r#"
+    query PlayerHeadToHead($id: ID!, $page: Int!, $perPage: Int!, $updatedAfter: Timestamp) {
+        player(id: $id) {
+            id
+            sets(page: $page, perPage: $perPage, filters: { updatedAfter: $updatedAfter }) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    fullRoundText
+                    displayScore
+                    completedAt
+                    state
+                    winnerId
+                    event {
+                        id
+                        name
+                        slug
+                        videogame {
+                            id
+                            name
+                        }
+                        tournament {
+                            id
+                            name
+                            slug
+                        }
+                    }
+                    games {
+                        winnerId
+                    }
+                    slots {
+                        entrant {
+                            id
+                            name
+                            participants {
+                                id
+                                gamerTag
+                                player {
+                                    id
+                                }
+                            }
+                        }
+                        standing {
+                            stats {
+                                score {
+                                    value
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    // Players are matched by their numeric id, so an id that isn't a number can't match anyone.
+    let player_id = |id: &GGID| match id {
+        GGID::Int(id) => Ok(*id),
+        GGID::String(value) => value.parse::<i64>().map_err(|_| format!("Player id {} is not a number", value)),
+    };
+    let (player_a_id, player_b_id) = (player_id(&player_a)?, player_id(&player_b)?);
+
+    let mut sets: Vec<GGSet> = Vec::new();
+    for id in [&player_a, &player_b] {
+        let vars = |page| PlayerVars {
+            id: id.clone(),
+            slug: "".to_string(),
+            page: page,
+            per_page: 20,
+            videogame_id: filter.videogame_id,
+            updated_after: filter.after_date.map(|date| date.timestamp()),
+        };
+        let response = execute_paginated_query(token, query, vars, |data| {
+            let sets = data.player.as_mut()?.sets.as_mut()?;
+            Some((sets.page_info().total_pages(), &mut sets.nodes))
+        }).await;
+        match response {
+            GGResponse::Data(data) => sets.append(&mut data.player().sets()),
+            GGResponse::Error(e) => return Err(e),
+        }
+    }
+
+    return Ok(HeadToHeadRecord::new(player_a_id, player_b_id, &sets, &filter));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn set(id: i64, winner_player: i64, score: (f64, f64), completed_at: i64, videogame_id: i64) -> GGSet {
+        let slot = |player_id: i64, score: f64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant {
+                id: Some(GGID::Int(player_id * 10)),
+                participants: Some(vec![GGParticipant { player: Some(Box::new(GGPlayer { id: Some(player_id), ..Default::default() })), ..Default::default() }]),
+                ..Default::default()
+            })),
+            standing: Some(Box::new(GGStanding {
+                stats: Some(Box::new(GGStandingStats { score: Some(Box::new(GGScore { value: Some(score), ..Default::default() })) })),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            winner_id: Some(winner_player * 10),
+            completed_at: Some(completed_at),
+            event: Some(Box::new(GGEvent {
+                id: Some(GGID::Int(id * 100)),
+                videogame: Some(Box::new(GGVideogame { id: Some(videogame_id), ..Default::default() })),
+                ..Default::default()
+            })),
+            slots: Some(vec![slot(1, score.0), slot(2, score.1)]),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn record_counts_sets_and_games() {
+        // The same set appears in both players' histories.
+        let sets = vec![set(1, 1, (3.0, 1.0), 200, 1), set(2, 2, (0.0, 2.0), 300, 1), set(3, 1, (2.0, 1.0), 100, 1), set(1, 1, (3.0, 1.0), 200, 1), set(4, 1, (2.0, 0.0), 400, 2)];
+
+        let record = HeadToHeadRecord::new(2, 1, &sets, &SetHistoryFilter { videogame_id: Some(1), ..Default::default() });
+        assert_eq!((record.set_wins, record.set_losses, record.game_wins, record.game_losses), (1, 2, 4, 5));
+        assert_eq!(record.most_recent().unwrap().set.id().to_string(), "2");
+        assert!(record.most_recent().unwrap().won);
+
+        let filter = SetHistoryFilter { after_date: Some(Utc.timestamp_opt(150, 0).unwrap()), ..Default::default() };
+        let record = HeadToHeadRecord::new(1, 2, &sets, &filter);
+        let meetings: Vec<String> = record.meetings.iter().map(|meeting| meeting.event.id().to_string()).collect();
+        assert_eq!(meetings, vec!["100", "200", "400"]);
+        assert!(HeadToHeadRecord::new(1, 3, &sets, &Default::default()).meetings.is_empty());
+
+        // A participant without a player isn't taken as player zero.
+        let mut unlinked = set(5, 1, (2.0, 0.0), 500, 1);
+        unlinked.slots.as_mut().unwrap()[1].entrant.as_mut().unwrap().participants.as_mut().unwrap()[0].player = None;
+        assert!(HeadToHeadRecord::new(1, 0, &[unlinked], &Default::default()).meetings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn player_ids_must_be_numbers() {
+        assert!(head_to_head(GGID::String("not-a-player".to_string()), GGID::Int(1), Default::default(), "").await.is_err());
+    }
+}
@@ -0,0 +1,449 @@
+//! Input objects used for serializing variables sent to the start.gg API.
+//!
+//! These structures are representations of existing input objects in the start.gg API.
+//! Each element is optional and is left out of the query when not set, so only the values given are used to filter.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::Serialize;
+
+use crate::{
+    enums::*,
+    set::*,
+};
+
+/// Equivalent for start.gg TournamentPageFilter.
+///
+/// Used to search for tournaments, see search_tournaments().
+#[derive(Clone, Default, Serialize)]
+pub struct TournamentPageFilter {
+
+    #[serde(rename(serialize = "videogameIds"),     skip_serializing_if = "Option::is_none")]
+    pub videogame_ids:      Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "countryCode"),      skip_serializing_if = "Option::is_none")]
+    pub country_code:       Option<String>,
+
+    #[serde(rename(serialize = "addrState"),        skip_serializing_if = "Option::is_none")]
+    pub addr_state:         Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location:           Option<TournamentLocationFilter>,
+
+    #[serde(rename(serialize = "afterDate"),        skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_seconds_option")]
+    pub after_date:         Option<DateTime<Utc>>,
+
+    #[serde(rename(serialize = "beforeDate"),       skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_seconds_option")]
+    pub before_date:        Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upcoming:           Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub past:               Option<bool>,
+
+    /// Set to false to only find offline tournaments.
+    #[serde(rename(serialize = "hasOnlineEvents"),  skip_serializing_if = "Option::is_none")]
+    pub has_online_events:  Option<bool>,
+
+    #[serde(rename(serialize = "ownerId"),          skip_serializing_if = "Option::is_none")]
+    pub owner_id:           Option<GGID>,
+
+}
+
+/// Equivalent for start.gg TournamentLocationFilter.
+///
+/// Finds tournaments within a distance (such as "50mi" or "25km") of a set of coordinates.
+#[derive(Clone, Default, Serialize)]
+pub struct TournamentLocationFilter {
+
+    #[serde(rename(serialize = "distanceFrom"))]
+    pub distance_from:      String,
+    pub distance:           String,
+
+}
+
+impl TournamentLocationFilter {
+
+    /// Creates a location filter from a latitude, longitude and radius.
+    pub fn new(lat: f64, lng: f64, distance: &str) -> TournamentLocationFilter {
+        return TournamentLocationFilter {
+            distance_from: format!("{},{}", lat, lng),
+            distance: distance.to_string(),
+        };
+    }
+
+}
+
+/// Equivalent for start.gg TournamentQuery.
+///
+/// Used to search for tournaments, see search_tournaments().
+#[derive(Clone, Default, Serialize)]
+pub struct TournamentQuery {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page:               Option<u32>,
+
+    #[serde(rename(serialize = "perPage"),          skip_serializing_if = "Option::is_none")]
+    pub per_page:           Option<u32>,
+
+    #[serde(rename(serialize = "sortBy"),           skip_serializing_if = "Option::is_none")]
+    pub sort_by:            Option<GGTournamentPaginationSort>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter:             Option<TournamentPageFilter>,
+
+}
+
+/// Equivalent for start.gg SetFilters.
+///
+/// Used to filter the sets of a phase group, phase or event, see get_phase_group_sets() and get_event_sets().
+#[derive(Clone, Default, Serialize)]
+pub struct SetFilters {
+
+    #[serde(rename(serialize = "entrantIds"),       skip_serializing_if = "Option::is_none")]
+    pub entrant_ids:        Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "entrantSize"),      skip_serializing_if = "Option::is_none")]
+    pub entrant_size:       Option<Vec<i64>>,
+
+    #[serde(rename(serialize = "hasVod"),           skip_serializing_if = "Option::is_none")]
+    pub has_vod:            Option<bool>,
+
+    /// Set to true to leave out sets without entrants.
+    #[serde(rename(serialize = "hideEmpty"),        skip_serializing_if = "Option::is_none")]
+    pub hide_empty:         Option<bool>,
+
+    /// Set to true to include sets with a bye.
+    #[serde(rename(serialize = "showByes"),         skip_serializing_if = "Option::is_none")]
+    pub show_byes:          Option<bool>,
+
+    #[serde(rename(serialize = "isEventOnline"),    skip_serializing_if = "Option::is_none")]
+    pub is_event_online:    Option<bool>,
+
+    #[serde(rename(serialize = "phaseGroupIds"),    skip_serializing_if = "Option::is_none")]
+    pub phase_group_ids:    Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "phaseIds"),         skip_serializing_if = "Option::is_none")]
+    pub phase_ids:          Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "playerIds"),        skip_serializing_if = "Option::is_none")]
+    pub player_ids:         Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "roundNumber"),      skip_serializing_if = "Option::is_none")]
+    pub round_number:       Option<i64>,
+
+    /// The states of the sets to include, see GGActivityState.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state:              Option<Vec<i64>>,
+
+    #[serde(rename(serialize = "stationIds"),       skip_serializing_if = "Option::is_none")]
+    pub station_ids:        Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "stationNumbers"),   skip_serializing_if = "Option::is_none")]
+    pub station_numbers:    Option<Vec<i64>>,
+
+    /// Only include sets updated after this time.
+    #[serde(rename(serialize = "updatedAfter"),     skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_seconds_option")]
+    pub updated_after:      Option<DateTime<Utc>>,
+
+}
+
+/// Equivalent for start.gg EntrantPageQuery.
+///
+/// Used to page through the entrants of an event.
+#[derive(Clone, Default, Serialize)]
+pub struct EntrantPageQuery {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page:               Option<u32>,
+
+    #[serde(rename(serialize = "perPage"),          skip_serializing_if = "Option::is_none")]
+    pub per_page:           Option<u32>,
+
+    #[serde(rename(serialize = "sortBy"),           skip_serializing_if = "Option::is_none")]
+    pub sort_by:            Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter:             Option<EntrantPageQueryFilter>,
+
+}
+
+/// Equivalent for start.gg EntrantPageQueryFilter.
+///
+/// Used to filter the entrants of an event, see get_event_entrants().
+#[derive(Clone, Default, Serialize)]
+pub struct EntrantPageQueryFilter {
+
+    /// Only include entrants with a name containing this value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name:               Option<String>,
+
+    #[serde(rename(serialize = "isDisqualified"),   skip_serializing_if = "Option::is_none")]
+    pub is_disqualified:    Option<bool>,
+
+    #[serde(rename(serialize = "updatedAfter"),     skip_serializing_if = "Option::is_none", with = "chrono::serde::ts_seconds_option")]
+    pub updated_after:      Option<DateTime<Utc>>,
+
+}
+
+/// Equivalent for start.gg StandingPaginationQuery.
+///
+/// Used to page through the standings of an event or phase group, see get_event_standings() and get_phase_group_standings().
+#[derive(Clone, Default, Serialize)]
+pub struct StandingPaginationQuery {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page:               Option<u32>,
+
+    #[serde(rename(serialize = "perPage"),          skip_serializing_if = "Option::is_none")]
+    pub per_page:           Option<u32>,
+
+    #[serde(rename(serialize = "sortBy"),           skip_serializing_if = "Option::is_none")]
+    pub sort_by:            Option<String>,
+
+}
+
+/// Equivalent for start.gg PhaseGroupPageQuery.
+///
+/// Used to filter the phase groups of a phase, see get_phase_phase_groups().
+#[derive(Clone, Default, Serialize)]
+pub struct PhaseGroupPageQuery {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page:               Option<u32>,
+
+    #[serde(rename(serialize = "perPage"),          skip_serializing_if = "Option::is_none")]
+    pub per_page:           Option<u32>,
+
+    #[serde(rename(serialize = "sortBy"),           skip_serializing_if = "Option::is_none")]
+    pub sort_by:            Option<String>,
+
+    /// Only include phase groups containing these entrants.
+    #[serde(rename(serialize = "entrantIds"),       skip_serializing_if = "Option::is_none")]
+    pub entrant_ids:        Option<Vec<GGID>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter:             Option<PhaseGroupPageQueryFilter>,
+
+}
+
+/// Equivalent for start.gg PhaseGroupPageQueryFilter.
+#[derive(Clone, Default, Serialize)]
+pub struct PhaseGroupPageQueryFilter {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id:                 Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "waveId"),           skip_serializing_if = "Option::is_none")]
+    pub wave_id:            Option<GGID>,
+
+}
+
+/// Equivalent for start.gg SeedPaginationQuery.
+///
+/// Used to filter the seeds of a phase or phase group, see get_phase_seeds() and get_phase_group_seeds().
+#[derive(Clone, Default, Serialize)]
+pub struct SeedPaginationQuery {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page:               Option<u32>,
+
+    #[serde(rename(serialize = "perPage"),          skip_serializing_if = "Option::is_none")]
+    pub per_page:           Option<u32>,
+
+    #[serde(rename(serialize = "sortBy"),           skip_serializing_if = "Option::is_none")]
+    pub sort_by:            Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter:             Option<SeedPageFilter>,
+
+}
+
+/// Equivalent for start.gg SeedPageFilter.
+#[derive(Clone, Default, Serialize)]
+pub struct SeedPageFilter {
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id:                 Option<GGID>,
+
+    #[serde(rename(serialize = "entrantName"),      skip_serializing_if = "Option::is_none")]
+    pub entrant_name:       Option<String>,
+
+    #[serde(rename(serialize = "phaseGroupId"),     skip_serializing_if = "Option::is_none")]
+    pub phase_group_id:     Option<Vec<GGID>>,
+
+    #[serde(rename(serialize = "phaseId"),          skip_serializing_if = "Option::is_none")]
+    pub phase_id:           Option<Vec<GGID>>,
+
+}
+
+/// Filter for a player's set history.
+///
+/// Unlike the other input objects, this filter is applied by the helpers themselves, see get_player() and get_user().
+/// Dates are compared against the time each set was completed, so sets that haven't been completed are left out when a date is given.
+#[derive(Clone, Default)]
+pub struct SetHistoryFilter {
+
+    pub videogame_id:       Option<i64>,
+    pub after_date:         Option<DateTime<Utc>>,
+    pub before_date:        Option<DateTime<Utc>>,
+
+}
+
+impl SetHistoryFilter {
+
+    /// Returns if the set passes the filter.
+    ///
+    /// The set's event and videogame need to be queried when filtering by videogame.
+    pub fn matches(&self, set: &GGSet) -> bool {
+        if self.videogame_id.is_some() && set.event().videogame().id() != self.videogame_id.unwrap() {
+            return false;
+        }
+        if self.after_date.is_some() || self.before_date.is_some() {
+            if set.completed_at.is_none() {
+                return false;
+            }
+            if self.after_date.is_some() && set.completed_at() < self.after_date.unwrap() {
+                return false;
+            }
+            if self.before_date.is_some() && set.completed_at() > self.before_date.unwrap() {
+                return false;
+            }
+        }
+        return true;
+    }
+
+}
+
+/// Equivalent for start.gg BracketSetGameDataInput.
+///
+/// The result of a single game in a set, see report_bracket_set().
+#[derive(Clone, Default, Serialize)]
+pub struct BracketSetGameDataInput {
+
+    #[serde(rename(serialize = "winnerId"),         skip_serializing_if = "Option::is_none")]
+    pub winner_id:          Option<GGID>,
+
+    #[serde(rename(serialize = "gameNum"))]
+    pub game_num:           i64,
+
+    #[serde(rename(serialize = "entrant1Score"),    skip_serializing_if = "Option::is_none")]
+    pub entrant1_score:     Option<i64>,
+
+    #[serde(rename(serialize = "entrant2Score"),    skip_serializing_if = "Option::is_none")]
+    pub entrant2_score:     Option<i64>,
+
+    #[serde(rename(serialize = "stageId"),          skip_serializing_if = "Option::is_none")]
+    pub stage_id:           Option<GGID>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selections:         Option<Vec<BracketSetGameSelectionInput>>,
+
+}
+
+/// Equivalent for start.gg BracketSetGameSelectionInput.
+///
+/// The character an entrant picked in a game.
+#[derive(Clone, Serialize)]
+pub struct BracketSetGameSelectionInput {
+
+    #[serde(rename(serialize = "entrantId"))]
+    pub entrant_id:         GGID,
+
+    #[serde(rename(serialize = "characterId"),      skip_serializing_if = "Option::is_none")]
+    pub character_id:       Option<i64>,
+
+}
+
+/// Equivalent for start.gg StationUpsertInput.
+///
+/// The values of a station being created or updated, see upsert_station().
+#[derive(Clone, Default, Serialize)]
+pub struct StationUpsertInput {
+
+    pub number:             i64,
+
+    #[serde(rename(serialize = "clusterId"),        skip_serializing_if = "Option::is_none")]
+    pub cluster_id:         Option<GGID>,
+
+}
+
+/// Equivalent for start.gg UpdatePhaseSeedInfo.
+///
+/// The new seed number of a seed, see update_phase_seeding().
+#[derive(Clone, Serialize)]
+pub struct UpdatePhaseSeedInfo {
+
+    #[serde(rename(serialize = "seedId"))]
+    pub seed_id:            GGID,
+
+    #[serde(rename(serialize = "seedNum"))]
+    pub seed_num:           i64,
+
+    #[serde(rename(serialize = "phaseGroupId"),     skip_serializing_if = "Option::is_none")]
+    pub phase_group_id:     Option<GGID>,
+
+}
+
+/// Equivalent for start.gg PhaseGroupUpdateInput.
+///
+/// The station and wave a phase group should be assigned to, see update_phase_groups().
+#[derive(Clone, Serialize)]
+pub struct PhaseGroupUpdateInput {
+
+    #[serde(rename(serialize = "phaseGroupId"))]
+    pub phase_group_id:     GGID,
+
+    #[serde(rename(serialize = "stationId"),        skip_serializing_if = "Option::is_none")]
+    pub station_id:         Option<GGID>,
+
+    #[serde(rename(serialize = "waveId"),           skip_serializing_if = "Option::is_none")]
+    pub wave_id:            Option<GGID>,
+
+}
+
+/// Equivalent for start.gg TournamentRegistrationInput.
+///
+/// The events to register for along with the registrant's details, see register_for_tournament().
+#[derive(Clone, Default, Serialize)]
+pub struct TournamentRegistrationInput {
+
+    #[serde(rename(serialize = "eventIds"))]
+    pub event_ids:          Vec<GGID>,
+
+    #[serde(rename(serialize = "gamerTag"),         skip_serializing_if = "Option::is_none")]
+    pub gamer_tag:          Option<String>,
+
+    #[serde(rename(serialize = "contactInfo"),      skip_serializing_if = "Option::is_none")]
+    pub contact_info:       Option<ContactInfoInput>,
+
+}
+
+/// Contact details of a registrant.
+///
+/// Mirrors GGContactInfo, see TournamentRegistrationInput.
+#[derive(Clone, Default, Serialize)]
+pub struct ContactInfoInput {
+
+    #[serde(rename(serialize = "nameFirst"),        skip_serializing_if = "Option::is_none")]
+    pub name_first:         Option<String>,
+
+    #[serde(rename(serialize = "nameLast"),         skip_serializing_if = "Option::is_none")]
+    pub name_last:          Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city:               Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state:              Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country:            Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zipcode:            Option<String>,
+
+}
@@ -1,22 +1,117 @@
 
 //! Communicate with start.gg's API in a fast, type-safe manner with little hassle.
 
+// Functions end with an explicit return and structures are initialised with explicit field names throughout the crate.
+#![allow(
+    clippy::needless_return,
+    clippy::redundant_field_names,
+)]
+
+use futures::Stream;
 use gql_client::{
     Client,
     ClientConfig,
 };
 
-use std::collections::HashMap;
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::RwLock,
+};
 use serde::Serialize;
 
+pub mod attendance;
+pub use attendance::*;
+
+pub mod bracket;
+pub use bracket::*;
+
+pub mod character_cache;
+pub use character_cache::*;
+
+pub mod character_stats;
+pub use character_stats::*;
+
+pub mod dry_run;
+pub use dry_run::*;
+
 pub mod enums;
 pub use enums::*;
 
+pub mod head_to_head;
+pub use head_to_head::*;
+
+pub mod inputs;
+pub use inputs::*;
+
+pub mod mutations;
+pub use mutations::*;
+
+pub mod pool;
+pub use pool::*;
+
+pub mod projection;
+pub use projection::*;
+
+pub mod ratings;
+pub use ratings::*;
+
+pub mod schedule;
+pub use schedule::*;
+
+pub mod seeding;
+pub use seeding::*;
+
+pub mod set_sync;
+pub use set_sync::*;
+
+pub mod station_tracker;
+pub use station_tracker::*;
+
+// The structures keep their original accessor style, which these lints flag.
+#[allow(
+    clippy::clone_on_copy,
+    clippy::empty_line_after_doc_comments,
+    clippy::large_enum_variant,
+    clippy::print_literal,
+    clippy::unnecessary_unwrap,
+)]
 pub mod structs;
 pub use structs::*;
 
+pub mod watcher;
+pub use watcher::*;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// The default endpoint of start.gg's API.
+const DEFAULT_ENDPOINT: &str = "https://api.start.gg/gql/alpha";
+
+/// The endpoint queries are sent to, or empty for the default endpoint.
+static ENDPOINT: RwLock<String> = RwLock::new(String::new());
+
+/// Set the endpoint queries are sent to.
+///
+/// Only the mock server in the testing module moves queries away from start.gg's API.
+#[cfg(feature = "testing")]
+pub(crate) fn set_endpoint(endpoint: &str) {
+    *ENDPOINT.write().unwrap() = endpoint.to_string();
+}
+
+/// Returns the endpoint queries are sent to.
+pub(crate) fn endpoint() -> String {
+    let endpoint = ENDPOINT.read().unwrap();
+    if endpoint.is_empty() {
+        return DEFAULT_ENDPOINT.to_string();
+    }
+    return endpoint.clone();
+}
+
 /// Variables for a query.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct Vars {
     pub id: GGID,
     pub slug: String,
@@ -24,37 +119,90 @@ pub struct Vars {
     pub per_page: u32,
 }
 
+/// Variables for a connection paged by an input object, such as EntrantPageQuery.
+#[derive(Clone, Serialize)]
+struct QueryVars<T: Serialize> {
+    id: GGID,
+    query: T,
+}
+
 /// Execute a query.
 ///
 /// When given a token, query, and a set of variables, this function will execute a query and return a deserialized object.
-pub async fn execute_query(
+/// Any serializable structure can be used for the variables, though most queries only need Vars.
+pub async fn execute_query<T: Serialize>(
     token: &str,
     query: &str,
-    vars: Vars,
+    vars: T,
 ) -> GGResponse {
 
     let mut headers = HashMap::new();
     headers.insert("authorization".to_string(), format!("Bearer {}", token));
 
     let config = ClientConfig {
-        endpoint: "https://api.start.gg/gql/alpha".to_string(),
+        endpoint: endpoint(),
         timeout: Some(60),
         headers: Some(headers),
         proxy: None,
     };
 
     let client = Client::new_with_config(config);
-    let data = client.query_with_vars::<GGResponse, Vars>(query, vars).await;
+    let data = client.query_with_vars::<GGResponse, T>(query, vars).await;
     let response: GGResponse;
     match data {
-        Ok(data) => response = data.unwrap(),
+        Ok(Some(data)) => response = data,
+        Ok(None) => response = GGResponse::Error(String::from("The response did not contain any data")),
         Err(e) => {
-            response = GGResponse::Error(String::from(e.message()));
+            // The message of each GraphQL error is only exposed through the formatted error.
+            let formatted = e.to_string();
+            let messages: Vec<&str> = formatted.lines().filter_map(|line| line.strip_prefix("Message: ")).collect();
+            if messages.is_empty() {
+                response = GGResponse::Error(String::from(e.message()));
+            } else if e.message().starts_with("The response is") {
+                response = GGResponse::Error(format!("{}: {}", e.message(), messages.join("; ")));
+            } else {
+                response = GGResponse::Error(messages.join("; "));
+            }
         },
     }
     return response;
 }
 
+/// Function returning the total number of pages and the nodes of a paginated connection.
+type PageNodes<T> = fn(&mut GGData) -> Option<(i64, &mut Vec<T>)>;
+
+/// Execute a query for every page of a connection.
+///
+/// The `vars` function should return the variables for a given page, starting from the first.
+/// Each remaining page is fetched and its nodes are appended to the first.
+/// The `nodes` function should return the total number of pages and the nodes of the connection being paginated.
+async fn execute_paginated_query<T, V: Serialize>(
+    token: &str,
+    query: &str,
+    vars: impl Fn(u32) -> V,
+    nodes: PageNodes<T>,
+) -> GGResponse {
+
+    let mut response = execute_query(token, query, vars(1)).await;
+    if let GGResponse::Data(data) = &mut response {
+        let total_pages = match nodes(data) {
+            Some((total_pages, _)) => total_pages,
+            None => return response,
+        };
+        for page in 2..=total_pages {
+            match execute_query(token, query, vars(page as u32)).await {
+                GGResponse::Data(mut next) => {
+                    if let (Some((_, current)), Some((_, next))) = (nodes(data), nodes(&mut next)) {
+                        current.append(next);
+                    }
+                },
+                GGResponse::Error(e) => return GGResponse::Error(e),
+            }
+        }
+    }
+    return response;
+}
+
 /// Get some basic tournament information.
 ///
 /// Returns the tournament id, name, slug, short slug, as well as a list of events, phases within those events, and all of the phase groups in each phase.
@@ -171,6 +319,49 @@ pub async fn get_phase_groups_from_phase(
     return execute_query(token, query, vars).await;
 }
 
+/// Get the phase groups of a phase that pass the query's filter.
+///
+/// Returns every matching phase group including its display identifier, state, start time and wave.
+/// The page is set by the helper, and the page size defaults to 100.
+pub async fn get_phase_phase_groups(
+    id: GGID,
+    page_query: PhaseGroupPageQuery,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PhasePhaseGroups($id: ID!, $query: PhaseGroupPageQuery) {
+        phase(id: $id) {
+            id
+            phaseGroups(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    displayIdentifier
+                    state
+                    startAt
+                    wave {
+                        id
+                        identifier
+                        startAt
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let per_page = page_query.per_page.unwrap_or(100);
+    let vars = |page| QueryVars { id: id.clone(), query: PhaseGroupPageQuery { page: Some(page), per_page: Some(per_page), ..page_query.clone() } };
+
+    return execute_paginated_query(token, query, vars, |data| {
+        let phase_groups = data.phase.as_mut()?.phase_groups.as_mut()?;
+        Some((phase_groups.page_info().total_pages(), &mut phase_groups.nodes))
+    }).await;
+}
+
 /// Get all of the sets in a given phase group.
 ///
 /// Returns a list of sets including the set name and entrants.
@@ -282,7 +473,1000 @@ pub async fn get_entrant_info(
     return execute_query(token, query, vars).await;
 }
 
+/// Get the final standings of an event.
+///
+/// Returns every standing in the event, sorted by placement, including the entrant, participants, score and whether the placement is final.
+/// Entrants that tied share the same placement. The page is set by the helper, and the page size defaults to 50.
+pub async fn get_event_standings(
+    id: GGID,
+    page_query: StandingPaginationQuery,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query EventStandings($id: ID!, $query: StandingPaginationQuery!) {
+        event(id: $id) {
+            id
+            standings(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    placement
+                    isFinal
+                    entrant {
+                        id
+                        name
+                        participants {
+                            id
+                            gamerTag
+                            prefix
+                        }
+                    }
+                    stats {
+                        score {
+                            label
+                            value
+                            displayValue
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let per_page = page_query.per_page.unwrap_or(50);
+    let vars = |page| QueryVars { id: id.clone(), query: StandingPaginationQuery { page: Some(page), per_page: Some(per_page), ..page_query.clone() } };
+
+    let mut response = execute_paginated_query(token, query, vars, |data| {
+        let standings = data.event.as_mut()?.standings.as_mut()?;
+        Some((standings.page_info().total_pages(), &mut standings.nodes))
+    }).await;
+    if let GGResponse::Data(data) = &mut response {
+        if let Some(standings) = data.event.as_mut().and_then(|event| event.standings.as_mut()) {
+            standings.sort_by_placement();
+        }
+    }
+    return response;
+}
+
+/// Get the standings of a phase group.
+///
+/// Returns every standing in the phase group, sorted by placement, including the entrant, participants, score and whether the placement is final.
+/// Entrants that tied share the same placement. The page is set by the helper, and the page size defaults to 50.
+pub async fn get_phase_group_standings(
+    id: GGID,
+    page_query: StandingPaginationQuery,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PhaseGroupStandings($id: ID!, $query: StandingPaginationQuery!) {
+        phaseGroup(id: $id) {
+            id
+            standings(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    placement
+                    isFinal
+                    entrant {
+                        id
+                        name
+                        participants {
+                            id
+                            gamerTag
+                            prefix
+                        }
+                    }
+                    stats {
+                        score {
+                            label
+                            value
+                            displayValue
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let per_page = page_query.per_page.unwrap_or(50);
+    let vars = |page| QueryVars { id: id.clone(), query: StandingPaginationQuery { page: Some(page), per_page: Some(per_page), ..page_query.clone() } };
+
+    let mut response = execute_paginated_query(token, query, vars, |data| {
+        let standings = data.phase_group.as_mut()?.standings.as_mut()?;
+        Some((standings.page_info().total_pages(), &mut standings.nodes))
+    }).await;
+    if let GGResponse::Data(data) = &mut response {
+        if let Some(standings) = data.phase_group.as_mut().and_then(|phase_group| phase_group.standings.as_mut()) {
+            standings.sort_by_placement();
+        }
+    }
+    return response;
+}
+
+/// Get the seeds of a phase.
+///
+/// Returns every seed in the phase that passes the query's filter, sorted by seed number, including the entrant, group seed number, byes, placeholders and where the seed progressed from.
+/// The page is set by the helper, and the page size defaults to 100.
+pub async fn get_phase_seeds(
+    id: GGID,
+    page_query: SeedPaginationQuery,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PhaseSeeds($id: ID!, $query: SeedPaginationQuery!) {
+        phase(id: $id) {
+            id
+            seeds(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    seedNum
+                    groupSeedNum
+                    isBye
+                    placeholderName
+                    entrant {
+                        id
+                        name
+                    }
+                    progressionSource {
+                        id
+                        originOrder
+                        originPlacement
+                        originPhase {
+                            id
+                            name
+                        }
+                        originPhaseGroup {
+                            id
+                            displayIdentifier
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let per_page = page_query.per_page.unwrap_or(100);
+    let vars = |page| QueryVars { id: id.clone(), query: SeedPaginationQuery { page: Some(page), per_page: Some(per_page), ..page_query.clone() } };
+
+    let mut response = execute_paginated_query(token, query, vars, |data| {
+        let seeds = data.phase.as_mut()?.seeds.as_mut()?;
+        Some((seeds.page_info().total_pages(), &mut seeds.nodes))
+    }).await;
+    if let GGResponse::Data(data) = &mut response {
+        if let Some(seeds) = data.phase.as_mut().and_then(|phase| phase.seeds.as_mut()) {
+            seeds.sort_by_seed_num();
+        }
+    }
+    return response;
+}
+
+/// Get the seeds of a phase group.
+///
+/// Returns every seed in the phase group that passes the query's filter, sorted by seed number, including the entrant, group seed number, byes, placeholders and where the seed progressed from.
+/// The page is set by the helper, and the page size defaults to 100.
+pub async fn get_phase_group_seeds(
+    id: GGID,
+    page_query: SeedPaginationQuery,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PhaseGroupSeeds($id: ID!, $query: SeedPaginationQuery!) {
+        phaseGroup(id: $id) {
+            id
+            seeds(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    seedNum
+                    groupSeedNum
+                    isBye
+                    placeholderName
+                    entrant {
+                        id
+                        name
+                    }
+                    progressionSource {
+                        id
+                        originOrder
+                        originPlacement
+                        originPhase {
+                            id
+                            name
+                        }
+                        originPhaseGroup {
+                            id
+                            displayIdentifier
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let per_page = page_query.per_page.unwrap_or(100);
+    let vars = |page| QueryVars { id: id.clone(), query: SeedPaginationQuery { page: Some(page), per_page: Some(per_page), ..page_query.clone() } };
+
+    let mut response = execute_paginated_query(token, query, vars, |data| {
+        let seeds = data.phase_group.as_mut()?.seeds.as_mut()?;
+        Some((seeds.page_info().total_pages(), &mut seeds.nodes))
+    }).await;
+    if let GGResponse::Data(data) = &mut response {
+        if let Some(seeds) = data.phase_group.as_mut().and_then(|phase_group| phase_group.seeds.as_mut()) {
+            seeds.sort_by_seed_num();
+        }
+    }
+    return response;
+}
+
+/// Variables for the sets of a phase group or event.
+#[derive(Clone, Serialize)]
+struct SetsVars {
+    id: GGID,
+    page: u32,
+
+    #[serde(rename(serialize = "perPage"))]
+    per_page: u32,
+
+    #[serde(rename(serialize = "sortType"))]
+    sort_type: GGSetSortType,
+    filters: SetFilters,
+}
+
+/// Get the sets of a phase group that pass the filters.
+///
+/// Returns every matching set including the round, state, winner, score, times, the prerequisites and entrant of each slot, and the set's station and stream.
+/// The filters are applied by start.gg, so only the matching sets are fetched.
+pub async fn get_phase_group_sets(
+    id: GGID,
+    filters: SetFilters,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PhaseGroupFilteredSets($id: ID!, $page: Int!, $perPage: Int!, $sortType: SetSortType, $filters: SetFilters) {
+        phaseGroup(id: $id) {
+            id
+            sets(page: $page, perPage: $perPage, sortType: $sortType, filters: $filters) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    identifier
+                    fullRoundText
+                    round
+                    state
+                    winnerId
+                    displayScore
+                    startedAt
+                    completedAt
+                    updatedAt
+                    wPlacement
+                    lPlacement
+                    slots {
+                        id
+                        slotIndex
+                        prereqId
+                        prereqType
+                        prereqPlacement
+                        entrant {
+                            id
+                            name
+                            initialSeedNum
+                        }
+                    }
+                    station {
+                        id
+                        number
+                    }
+                    stream {
+                        id
+                        streamName
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| SetsVars { id: id.clone(), page: page, per_page: 40, sort_type: GGSetSortType::Standard, filters: filters.clone() };
+
+    return execute_paginated_query(token, query, vars, |data| {
+        let sets = data.phase_group.as_mut()?.sets.as_mut()?;
+        Some((sets.page_info().total_pages(), &mut sets.nodes))
+    }).await;
+}
+
+/// Get the sets of an event that pass the filters.
+///
+/// Returns every matching set including the round, state, winner, score, times, the prerequisites and entrant of each slot, and the set's station and stream.
+/// The filters are applied by start.gg, so only the matching sets are fetched.
+pub async fn get_event_sets(
+    id: GGID,
+    filters: SetFilters,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query EventFilteredSets($id: ID!, $page: Int!, $perPage: Int!, $sortType: SetSortType, $filters: SetFilters) {
+        event(id: $id) {
+            id
+            sets(page: $page, perPage: $perPage, sortType: $sortType, filters: $filters) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    identifier
+                    fullRoundText
+                    round
+                    state
+                    winnerId
+                    displayScore
+                    startedAt
+                    completedAt
+                    updatedAt
+                    wPlacement
+                    lPlacement
+                    slots {
+                        id
+                        slotIndex
+                        prereqId
+                        prereqType
+                        prereqPlacement
+                        entrant {
+                            id
+                            name
+                            initialSeedNum
+                        }
+                    }
+                    station {
+                        id
+                        number
+                    }
+                    stream {
+                        id
+                        streamName
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| SetsVars { id: id.clone(), page: page, per_page: 40, sort_type: GGSetSortType::Standard, filters: filters.clone() };
+
+    return execute_paginated_query(token, query, vars, |data| {
+        let sets = data.event.as_mut()?.sets.as_mut()?;
+        Some((sets.page_info().total_pages(), &mut sets.nodes))
+    }).await;
+}
+
+
+/// Get the entrants of an event that pass the filter.
+///
+/// Returns every matching entrant including the initial seed, whether they were disqualified, and the tag and prefix of each participant.
+pub async fn get_event_entrants(
+    id: GGID,
+    filter: EntrantPageQueryFilter,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query EventEntrants($id: ID!, $query: EntrantPageQuery!) {
+        event(id: $id) {
+            id
+            entrants(query: $query) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    name
+                    initialSeedNum
+                    isDisqualified
+                    participants {
+                        id
+                        gamerTag
+                        prefix
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| QueryVars {
+        id: id.clone(),
+        query: EntrantPageQuery { page: Some(page), per_page: Some(50), sort_by: None, filter: Some(filter.clone()) },
+    };
+
+    return execute_paginated_query(token, query, vars, |data| {
+        let entrants = data.event.as_mut()?.entrants.as_mut()?;
+        Some((entrants.page_info().total_pages(), &mut entrants.nodes))
+    }).await;
+}
+
+/// Variables for a player's profile and set history.
+#[derive(Clone, Serialize)]
+struct PlayerVars {
+    id: GGID,
+    slug: String,
+    page: u32,
+
+    #[serde(rename(serialize = "perPage"))]
+    per_page: u32,
+
+    #[serde(rename(serialize = "videogameId"))]
+    videogame_id: Option<i64>,
+
+    #[serde(rename(serialize = "updatedAfter"))]
+    updated_after: Option<i64>,
+}
+
+/// Get a user's profile and set history.
+///
+/// Returns the user, their player (prefix and gamer tag), rankings, recent standings and every set they have played that passes the filter.
+/// Rankings and recent standings are limited to the filter's videogame when one is given.
+pub async fn get_user(
+    slug: &str,
+    filter: SetHistoryFilter,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query UserProfile($slug: String!, $videogameId: ID) {
+        user(slug: $slug) {
+            id
+            slug
+            name
+            discriminator
+            bio
+            genderPronoun
+            player {
+                id
+                prefix
+                gamerTag
+                rankings(videogameId: $videogameId) {
+                    id
+                    rank
+                    title
+                }
+                recentStandings(videogameId: $videogameId, limit: 10) {
+                    id
+                    placement
+                    entrant {
+                        id
+                        name
+                        event {
+                            id
+                            name
+                            slug
+                            startAt
+                            tournament {
+                                id
+                                name
+                                slug
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let sets_query = r#"
+    query UserSets($slug: String!, $page: Int!, $perPage: Int!, $updatedAfter: Timestamp) {
+        user(slug: $slug) {
+            id
+            player {
+                id
+                sets(page: $page, perPage: $perPage, filters: { updatedAfter: $updatedAfter }) {
+                    pageInfo {
+                        totalPages
+                    }
+                    nodes {
+                        id
+                        fullRoundText
+                        displayScore
+                        completedAt
+                        winnerId
+                        event {
+                            id
+                            name
+                            slug
+                            videogame {
+                                id
+                                name
+                            }
+                            tournament {
+                                id
+                                name
+                                slug
+                            }
+                        }
+                        slots {
+                            entrant {
+                                id
+                                name
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| PlayerVars {
+        id: GGID::Int(0),
+        slug: slug.to_string(),
+        page: page,
+        per_page: 25,
+        videogame_id: filter.videogame_id,
+        updated_after: filter.after_date.map(|date| date.timestamp()),
+    };
+
+    // The profile is fetched once, as the set history is paginated separately.
+    let mut response = execute_query(token, query, vars(1)).await;
+    if let GGResponse::Data(data) = &mut response {
+        let sets = match execute_paginated_query(token, sets_query, vars, |data| {
+            let sets = data.user.as_mut()?.player.as_mut()?.sets.as_mut()?;
+            Some((sets.page_info().total_pages(), &mut sets.nodes))
+        }).await {
+            GGResponse::Data(sets) => sets.user.and_then(|user| user.player).and_then(|player| player.sets),
+            GGResponse::Error(e) => return GGResponse::Error(e),
+        };
+        if let Some(player) = data.user.as_mut().and_then(|user| user.player.as_mut()) {
+            player.sets = sets.map(|mut sets| {
+                sets.nodes.retain(|set| filter.matches(set));
+                sets
+            });
+        }
+    }
+    return response;
+}
+
+/// Get a player's profile and set history.
+///
+/// Returns the player (prefix and gamer tag), their user, rankings, recent standings and every set they have played that passes the filter.
+/// Rankings and recent standings are limited to the filter's videogame when one is given.
+pub async fn get_player(
+    id: GGID,
+    filter: SetHistoryFilter,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query PlayerProfile($id: ID!, $videogameId: ID) {
+        player(id: $id) {
+            id
+            prefix
+            gamerTag
+            rankings(videogameId: $videogameId) {
+                id
+                rank
+                title
+            }
+            recentStandings(videogameId: $videogameId, limit: 10) {
+                id
+                placement
+                entrant {
+                    id
+                    name
+                    event {
+                        id
+                        name
+                        slug
+                        startAt
+                        tournament {
+                            id
+                            name
+                            slug
+                        }
+                    }
+                }
+            }
+            user {
+                id
+                slug
+                name
+                discriminator
+            }
+        }
+    }
+    "#;
+
+    let sets_query = r#"
+    query PlayerSets($id: ID!, $page: Int!, $perPage: Int!, $updatedAfter: Timestamp) {
+        player(id: $id) {
+            id
+            sets(page: $page, perPage: $perPage, filters: { updatedAfter: $updatedAfter }) {
+                pageInfo {
+                    totalPages
+                }
+                nodes {
+                    id
+                    fullRoundText
+                    displayScore
+                    completedAt
+                    winnerId
+                    event {
+                        id
+                        name
+                        slug
+                        videogame {
+                            id
+                            name
+                        }
+                        tournament {
+                            id
+                            name
+                            slug
+                        }
+                    }
+                    slots {
+                        entrant {
+                            id
+                            name
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| PlayerVars {
+        id: id.clone(),
+        slug: "".to_string(),
+        page: page,
+        per_page: 25,
+        videogame_id: filter.videogame_id,
+        updated_after: filter.after_date.map(|date| date.timestamp()),
+    };
+
+    // The profile is fetched once, as the set history is paginated separately.
+    let mut response = execute_query(token, query, vars(1)).await;
+    if let GGResponse::Data(data) = &mut response {
+        let sets = match execute_paginated_query(token, sets_query, vars, |data| {
+            let sets = data.player.as_mut()?.sets.as_mut()?;
+            Some((sets.page_info().total_pages(), &mut sets.nodes))
+        }).await {
+            GGResponse::Data(sets) => sets.player.and_then(|player| player.sets),
+            GGResponse::Error(e) => return GGResponse::Error(e),
+        };
+        if let Some(player) = data.player.as_mut() {
+            player.sets = sets.map(|mut sets| {
+                sets.nodes.retain(|set| filter.matches(set));
+                sets
+            });
+        }
+    }
+    return response;
+}
+
+/// Variables for a videogame lookup or search.
+#[derive(Clone, Serialize)]
+struct VideogameVars {
+    id: Option<GGID>,
+    slug: Option<String>,
+    name: Option<String>,
+    page: u32,
+
+    #[serde(rename(serialize = "perPage"))]
+    per_page: u32,
+}
+
+/// Get a videogame and its characters.
+///
+/// A GGID::Int is looked up by id, while a GGID::String is looked up by slug (such as "game/ultimate").
+/// Returns the videogame's id, names, slug, images and every character with their images.
+pub async fn get_videogame(
+    id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query Videogame($id: ID, $slug: String) {
+        videogame(id: $id, slug: $slug) {
+            id
+            name
+            displayName
+            slug
+            images {
+                id
+                type
+                url
+            }
+            characters {
+                id
+                name
+                images {
+                    id
+                    type
+                    url
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = match id {
+        GGID::Int(_) => VideogameVars { id: Some(id), slug: None, name: None, page: 1, per_page: 1 },
+        GGID::String(slug) => VideogameVars { id: None, slug: Some(slug), name: None, page: 1, per_page: 1 },
+    };
+
+    return execute_query(token, query, vars).await;
+}
+
+/// Search for videogames by name.
+///
+/// Returns every videogame matching the name, without their characters (see get_videogame()).
+pub async fn search_videogames(
+    name: &str,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    query SearchVideogames($name: String, $page: Int!, $perPage: Int!) {
+        videogames(query: { page: $page, perPage: $perPage, filter: { name: $name } }) {
+            pageInfo {
+                totalPages
+            }
+            nodes {
+                id
+                name
+                displayName
+                slug
+            }
+        }
+    }
+    "#;
+
+    let vars = |page| VideogameVars { id: None, slug: None, name: Some(name.to_string()), page: page, per_page: 100 };
+
+    return execute_paginated_query(token, query, vars, |data| {
+        let videogames = data.videogames.as_mut()?;
+        Some((videogames.page_info().total_pages(), &mut videogames.nodes))
+    }).await;
+}
+
+/// Variables for a tournament search.
+#[derive(Clone, Serialize)]
+struct TournamentSearchVars {
+    query: TournamentQuery,
+}
+
+/// Search for tournaments.
+///
+/// Returns a stream of every tournament matching the filter, fetching each page as the previous one is used up.
+/// If a page fails to load, the error is returned as the last item of the stream.
+pub fn search_tournaments(
+    filter: TournamentPageFilter,
+    sort_by: Option<GGTournamentPaginationSort>,
+    token: &str,
+) -> impl Stream<Item = Result<GGTournament, String>> {
+
+    let query = r#"
+    query SearchTournaments($query: TournamentQuery!) {
+        tournaments(query: $query) {
+            pageInfo {
+                totalPages
+            }
+            nodes {
+                id
+                name
+                slug
+                shortSlug
+                startAt
+                endAt
+                city
+                addrState
+                countryCode
+                lat
+                lng
+                isOnline
+                isRegistrationOpen
+                numAttendees
+            }
+        }
+    }
+    "#;
+
+    let vars = TournamentSearchVars {
+        query: TournamentQuery { page: Some(0), per_page: Some(50), sort_by: sort_by, filter: Some(filter) },
+    };
+    let state = (token.to_string(), vars, VecDeque::<GGTournament>::new(), i64::MAX);
+
+    return futures::stream::unfold(Some(state), move |state| async move {
+        let (token, mut vars, mut nodes, mut total_pages) = state?;
+        loop {
+            if let Some(tournament) = nodes.pop_front() {
+                return Some((Ok(tournament), Some((token, vars, nodes, total_pages))));
+            }
+            let page = vars.query.page.unwrap_or(0);
+            if (page as i64) >= total_pages {
+                return None;
+            }
+            vars.query.page = Some(page + 1);
+            match execute_query(&token, query, vars.clone()).await {
+                GGResponse::Data(data) => {
+                    let tournaments = data.tournaments.unwrap_or_default();
+                    total_pages = tournaments.page_info().total_pages();
+                    if tournaments.nodes.is_empty() {
+                        return None;
+                    }
+                    nodes.extend(tournaments.nodes);
+                },
+                GGResponse::Error(e) => return Some((Err(e), None)),
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    use chrono::{
+        TimeZone,
+        Utc,
+    };
+
+    fn standing(id: i64, placement: i64) -> GGStanding {
+        GGStanding { id: Some(id), placement: Some(placement), ..Default::default() }
+    }
+
+    fn seed(id: i64, seed_num: i64) -> GGSeed {
+        GGSeed { id: Some(id), seed_num: Some(seed_num), ..Default::default() }
+    }
+
+    #[test]
+    fn standings_sort_by_placement_keeping_ties() {
+        let mut standings = GGStandingConnection {
+            nodes: vec![standing(3, 5), standing(1, 1), standing(4, 5), standing(2, 2), standing(1, 1)],
+            page_info: None,
+        };
+        standings.sort_by_placement();
+
+        let result: Vec<(i64, i64)> = standings.nodes.iter().map(|s| (s.id(), s.placement())).collect();
+        assert_eq!(result, vec![(1, 1), (2, 2), (3, 5), (4, 5)]);
+    }
+
+    #[test]
+    fn seeds_sort_by_seed_num() {
+        let mut seeds = GGSeedConnection {
+            nodes: vec![seed(30, 3), seed(10, 1), seed(20, 2), seed(10, 1)],
+            page_info: None,
+        };
+        seeds.sort_by_seed_num();
+
+        let result: Vec<i64> = seeds.nodes.iter().map(|s| s.seed_num()).collect();
+        assert_eq!(result, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn tournament_filter_only_serializes_set_values() {
+        let filter = TournamentPageFilter {
+            videogame_ids: Some(vec![GGID::Int(1386)]),
+            location: Some(TournamentLocationFilter::new(33.7, -117.8, "50mi")),
+            after_date: Some(Utc.timestamp_opt(1700000000, 0).unwrap()),
+            has_online_events: Some(false),
+            ..Default::default()
+        };
+        let vars = TournamentSearchVars {
+            query: TournamentQuery { page: Some(1), per_page: Some(50), sort_by: Some(GGTournamentPaginationSort::StartAt), filter: Some(filter) },
+        };
+
+        assert_eq!(
+            serde_json::to_string(&vars).unwrap(),
+            r#"{"query":{"page":1,"perPage":50,"sortBy":"startAt","filter":{"videogameIds":[1386],"location":{"distanceFrom":"33.7,-117.8","distance":"50mi"},"afterDate":1700000000,"hasOnlineEvents":false}}}"#
+        );
+    }
+
+    #[test]
+    fn set_filters_only_serialize_set_values() {
+        let filters = SetFilters {
+            state: Some(vec![GGActivityState::Active as i64, GGActivityState::Called as i64]),
+            hide_empty: Some(true),
+            station_ids: Some(vec![GGID::Int(12)]),
+            updated_after: Some(Utc.timestamp_opt(1700000000, 0).unwrap()),
+            ..Default::default()
+        };
+        let vars = SetsVars { id: GGID::Int(5), page: 1, per_page: 40, sort_type: GGSetSortType::CallOrder, filters: filters };
+
+        assert_eq!(
+            serde_json::to_string(&vars).unwrap(),
+            r#"{"id":5,"page":1,"perPage":40,"sortType":"CALL_ORDER","filters":{"hideEmpty":true,"state":[2,6],"stationIds":[12],"updatedAfter":1700000000}}"#
+        );
+    }
+
+    #[test]
+    fn set_history_filter_matches_videogame_and_dates() {
+        let set = |videogame_id: i64, completed_at: Option<i64>| GGSet {
+            completed_at: completed_at,
+            event: Some(Box::new(GGEvent {
+                videogame: Some(Box::new(GGVideogame { id: Some(videogame_id), ..Default::default() })),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let filter = SetHistoryFilter {
+            videogame_id: Some(1386),
+            after_date: Some(Utc.timestamp_opt(1000, 0).unwrap()),
+            before_date: Some(Utc.timestamp_opt(2000, 0).unwrap()),
+        };
+
+        assert!(filter.matches(&set(1386, Some(1500))));
+        assert!(!filter.matches(&set(1, Some(1500))));
+        assert!(!filter.matches(&set(1386, Some(500))));
+        assert!(!filter.matches(&set(1386, Some(2500))));
+        assert!(!filter.matches(&set(1386, None)));
+        assert!(SetHistoryFilter::default().matches(&set(1, None)));
+    }
+
+    #[test]
+    fn character_cache_resolves_selections() {
+        let character = |id: i64, name: &str| GGCharacter { id: Some(id), name: Some(name.to_string()), images: None };
+        let videogame = GGVideogame {
+            id: Some(1386),
+            characters: Some(vec![character(1271, "Bayonetta"), character(1302, "Mario")]),
+            ..Default::default()
+        };
+        let mut cache = CharacterCache::new();
+        cache.insert(&videogame);
+
+        let selection = GGGameSelection { selection_value: Some(1302), ..Default::default() };
+        assert_eq!(cache.selection_character(&selection).unwrap().name(), "Mario");
+        assert_eq!(cache.character_name(1271), "Bayonetta");
+        assert_eq!(cache.character_name(1), "");
+        assert_eq!(cache.characters(1386).len(), 2);
+        assert!(cache.characters(1).is_empty());
+    }
+
+    #[test]
+    fn enum_numbers_and_names_deserialize() {
+        let selections: Vec<GGGameSelection> = serde_json::from_str(r#"[{"selectionType":"CHARACTER"},{"selectionType":1},{"selectionType":null},{}]"#).unwrap();
+        let types: Vec<Option<i64>> = selections.iter().map(|selection| selection.selection_type).collect();
+        assert_eq!(types, vec![Some(1), Some(1), None, None]);
+
+        let phase_group: GGPhaseGroup = serde_json::from_str(r#"{"bracketType":"ROUND_ROBIN"}"#).unwrap();
+        assert_eq!(phase_group.bracket_type(), GGBracketType::RoundRobin as i64);
+    }
+
+    #[test]
+    fn bracket_set_game_data_serializes() {
+        let game = BracketSetGameDataInput {
+            winner_id: Some(GGID::Int(10)),
+            game_num: 1,
+            entrant1_score: Some(3),
+            entrant2_score: Some(0),
+            stage_id: Some(GGID::Int(7)),
+            selections: Some(vec![BracketSetGameSelectionInput { entrant_id: GGID::Int(10), character_id: Some(1302) }]),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&game).unwrap(),
+            r#"{"winnerId":10,"gameNum":1,"entrant1Score":3,"entrant2Score":0,"stageId":7,"selections":[{"entrantId":10,"characterId":1302}]}"#
+        );
+    }
 }
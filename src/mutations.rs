@@ -0,0 +1,634 @@
+//! Mutations used for changing data on start.gg.
+//!
+//! Apart from registering, each mutation requires a token belonging to a user with permission to manage the tournament.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::Serialize;
+
+use crate::{
+    enums::*,
+    execute_query,
+    get_phase_seeds,
+    inputs::*,
+    structs::*,
+};
+
+/// Execute a mutation.
+///
+/// Works the same as execute_query(), except errors caused by the token not having permission to manage the tournament are replaced with a clearer message.
+async fn execute_mutation<T: Serialize>(
+    token: &str,
+    query: &str,
+    vars: T,
+) -> GGResponse {
+
+    let response = execute_query(token, query, vars).await;
+    if let GGResponse::Error(e) = &response {
+        if is_permission_error(e) {
+            return GGResponse::Error(format!("The token does not have permission to manage this tournament ({})", e));
+        }
+    }
+    return response;
+}
+
+/// Returns if an error was caused by the token not having permission to make a change.
+fn is_permission_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    return ["permission", "unauthorized", "not authorized", "forbidden", "[401]", "[403]"]
+        .iter()
+        .any(|pattern| error.contains(pattern));
+}
+
+/// Variables for changing a set.
+#[derive(Clone, Serialize)]
+struct SetVars {
+
+    #[serde(rename(serialize = "setId"))]
+    set_id: GGID,
+
+    #[serde(rename(serialize = "resetDependentSets"))]
+    reset_dependent_sets: Option<bool>,
+
+    #[serde(rename(serialize = "gameData"))]
+    game_data: Option<Vec<BracketSetGameDataInput>>,
+
+}
+
+/// Variables for reporting a set.
+#[derive(Clone, Serialize)]
+struct ReportBracketSetVars {
+
+    #[serde(rename(serialize = "setId"))]
+    set_id: GGID,
+
+    #[serde(rename(serialize = "winnerId"))]
+    winner_id: GGID,
+
+    #[serde(rename(serialize = "isDQ"))]
+    is_dq: bool,
+
+    #[serde(rename(serialize = "gameData"))]
+    game_data: Option<Vec<BracketSetGameDataInput>>,
+
+}
+
+/// Report the result of a set.
+///
+/// When `is_dq` is true the loser is disqualified and any game data is ignored.
+/// Returns the reported set along with any sets that were created or updated by it (see GGData::report_bracket_set()), such as the sets the winner and loser progress to.
+pub async fn report_bracket_set(
+    set_id: GGID,
+    winner_id: GGID,
+    is_dq: bool,
+    game_data: Vec<BracketSetGameDataInput>,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation ReportBracketSet($setId: ID!, $winnerId: ID, $isDQ: Boolean, $gameData: [BracketSetGameDataInput]) {
+        reportBracketSet(setId: $setId, winnerId: $winnerId, isDQ: $isDQ, gameData: $gameData) {
+            id
+            state
+            winnerId
+            displayScore
+            fullRoundText
+            identifier
+            round
+            completedAt
+            slots {
+                id
+                entrant {
+                    id
+                    name
+                }
+            }
+        }
+    }
+    "#;
+
+    let game_data = if is_dq || game_data.is_empty() { None } else { Some(game_data) };
+    let vars = ReportBracketSetVars { set_id: set_id, winner_id: winner_id, is_dq: is_dq, game_data: game_data };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Mark a set as called.
+///
+/// Returns the updated set (see GGData::mark_set_called()).
+pub async fn mark_set_called(
+    set_id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation MarkSetCalled($setId: ID!) {
+        markSetCalled(setId: $setId) {
+            id
+            state
+            fullRoundText
+            identifier
+            startedAt
+        }
+    }
+    "#;
+
+    let vars = SetVars { set_id: set_id, reset_dependent_sets: None, game_data: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Mark a set as in progress.
+///
+/// Returns the updated set (see GGData::mark_set_in_progress()).
+pub async fn mark_set_in_progress(
+    set_id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation MarkSetInProgress($setId: ID!) {
+        markSetInProgress(setId: $setId) {
+            id
+            state
+            fullRoundText
+            identifier
+            startedAt
+        }
+    }
+    "#;
+
+    let vars = SetVars { set_id: set_id, reset_dependent_sets: None, game_data: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Reset a set.
+///
+/// When `reset_dependent_sets` is true, any sets that depend on the result of this set are reset as well.
+/// Returns the updated set (see GGData::reset_set()).
+pub async fn reset_set(
+    set_id: GGID,
+    reset_dependent_sets: bool,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation ResetSet($setId: ID!, $resetDependentSets: Boolean) {
+        resetSet(setId: $setId, resetDependentSets: $resetDependentSets) {
+            id
+            state
+            winnerId
+            displayScore
+            fullRoundText
+            identifier
+            slots {
+                id
+                entrant {
+                    id
+                    name
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = SetVars { set_id: set_id, reset_dependent_sets: Some(reset_dependent_sets), game_data: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Update the game data of a set without completing it.
+///
+/// Returns the updated set (see GGData::update_bracket_set()).
+pub async fn update_bracket_set(
+    set_id: GGID,
+    game_data: Vec<BracketSetGameDataInput>,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation UpdateBracketSet($setId: ID!, $gameData: [BracketSetGameDataInput]) {
+        updateBracketSet(setId: $setId, gameData: $gameData) {
+            id
+            state
+            winnerId
+            displayScore
+            fullRoundText
+            identifier
+            games {
+                id
+                orderNum
+                winnerId
+            }
+        }
+    }
+    "#;
+
+    let vars = SetVars { set_id: set_id, reset_dependent_sets: None, game_data: Some(game_data) };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Variables for changing a station or assigning a set.
+#[derive(Clone, Serialize)]
+struct StationVars {
+
+    #[serde(rename(serialize = "tournamentId"))]
+    tournament_id: Option<GGID>,
+
+    #[serde(rename(serialize = "stationId"))]
+    station_id: Option<GGID>,
+
+    #[serde(rename(serialize = "streamId"))]
+    stream_id: Option<GGID>,
+
+    #[serde(rename(serialize = "setId"))]
+    set_id: Option<GGID>,
+    fields: Option<StationUpsertInput>,
+
+}
+
+/// Create or update a station.
+///
+/// A new station is created in the tournament when `station_id` is None, otherwise the existing station is updated.
+/// Returns the station (see GGData::upsert_station()).
+pub async fn upsert_station(
+    tournament_id: GGID,
+    station_id: Option<GGID>,
+    fields: StationUpsertInput,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation UpsertStation($stationId: ID, $tournamentId: ID, $fields: StationUpsertInput!) {
+        upsertStation(stationId: $stationId, tournamentId: $tournamentId, fields: $fields) {
+            id
+            number
+            identifier
+            enabled
+            state
+        }
+    }
+    "#;
+
+    let vars = StationVars { tournament_id: Some(tournament_id), station_id: station_id, stream_id: None, set_id: None, fields: Some(fields) };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Delete a station.
+///
+/// Returns if the station was deleted (see GGData::delete_station()).
+pub async fn delete_station(
+    station_id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation DeleteStation($stationId: ID!) {
+        deleteStation(stationId: $stationId)
+    }
+    "#;
+
+    let vars = StationVars { tournament_id: None, station_id: Some(station_id), stream_id: None, set_id: None, fields: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Assign a set to a station.
+///
+/// Returns the updated set (see GGData::assign_station()).
+pub async fn assign_station(
+    set_id: GGID,
+    station_id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation AssignStation($setId: ID!, $stationId: ID!) {
+        assignStation(setId: $setId, stationId: $stationId) {
+            id
+            state
+            fullRoundText
+            identifier
+            station {
+                id
+                number
+            }
+        }
+    }
+    "#;
+
+    let vars = StationVars { tournament_id: None, station_id: Some(station_id), stream_id: None, set_id: Some(set_id), fields: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Assign a set to a stream.
+///
+/// Returns the updated set (see GGData::assign_stream()).
+pub async fn assign_stream(
+    set_id: GGID,
+    stream_id: GGID,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation AssignStream($setId: ID!, $streamId: ID!) {
+        assignStream(setId: $setId, streamId: $streamId) {
+            id
+            state
+            fullRoundText
+            identifier
+            stream {
+                id
+                streamName
+                streamSource
+            }
+        }
+    }
+    "#;
+
+    let vars = StationVars { tournament_id: None, station_id: None, stream_id: Some(stream_id), set_id: Some(set_id), fields: None };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Variables for updating seeding or phase groups.
+#[derive(Clone, Serialize)]
+struct PhaseVars {
+
+    #[serde(rename(serialize = "phaseId"))]
+    phase_id: Option<GGID>,
+
+    #[serde(rename(serialize = "seedMapping"))]
+    seed_mapping: Option<Vec<UpdatePhaseSeedInfo>>,
+
+    #[serde(rename(serialize = "groupConfigs"))]
+    group_configs: Option<Vec<PhaseGroupUpdateInput>>,
+
+}
+
+/// Returns an error if the seeds don't make up a permutation.
+///
+/// There must be at least one seed. Each seed must only appear once, and the seed numbers must run from 1 to the number of seeds without any gaps or duplicates.
+fn validate_seed_mapping(seeds: &[(GGID, i64)]) -> Result<(), String> {
+    if seeds.is_empty() {
+        return Err(String::from("No seeds were given"));
+    }
+    let mut seed_ids: Vec<&GGID> = Vec::new();
+    let mut seed_nums: Vec<bool> = vec![false; seeds.len()];
+    for (seed_id, seed_num) in seeds {
+        if seed_ids.contains(&seed_id) {
+            return Err(String::from("A seed was given more than once"));
+        }
+        seed_ids.push(seed_id);
+        if *seed_num < 1 || *seed_num as usize > seeds.len() {
+            return Err(format!("Seed number {} is outside of 1 to {}", seed_num, seeds.len()));
+        }
+        if seed_nums[*seed_num as usize - 1] {
+            return Err(format!("Seed number {} was given more than once", seed_num));
+        }
+        seed_nums[*seed_num as usize - 1] = true;
+    }
+    return Ok(());
+}
+
+/// Update the seeding of a phase.
+///
+/// Each seed is given as a seed id and its new seed number, which must make up a permutation of every seed in the phase.
+/// The seeding is checked before anything is sent, returning an error if it isn't a permutation.
+/// Returns the phase with its updated seeds sorted by seed number (see GGData::update_phase_seeding()).
+/// The seeds are fetched with get_phase_seeds() once the mutation succeeds, as a large phase has more seeds than a single response can return.
+pub async fn update_phase_seeding(
+    phase_id: GGID,
+    seeds: Vec<(GGID, i64)>,
+    token: &str,
+) -> GGResponse {
+
+    if let Err(e) = validate_seed_mapping(&seeds) {
+        return GGResponse::Error(e);
+    }
+
+    let query = r#"
+    mutation UpdatePhaseSeeding($phaseId: ID!, $seedMapping: [UpdatePhaseSeedInfo]!) {
+        updatePhaseSeeding(phaseId: $phaseId, seedMapping: $seedMapping) {
+            id
+            name
+        }
+    }
+    "#;
+
+    let seed_mapping = seeds.into_iter().map(|(seed_id, seed_num)| UpdatePhaseSeedInfo { seed_id: seed_id, seed_num: seed_num, phase_group_id: None }).collect();
+    let vars = PhaseVars { phase_id: Some(phase_id.clone()), seed_mapping: Some(seed_mapping), group_configs: None };
+
+    let mut response = execute_mutation(token, query, vars).await;
+    if let GGResponse::Data(data) = &mut response {
+        let seeds = match get_phase_seeds(phase_id, Default::default(), token).await {
+            GGResponse::Data(seeds) => seeds.phase.and_then(|phase| phase.seeds),
+            GGResponse::Error(e) => return GGResponse::Error(format!("The seeding was updated, but the seeds could not be fetched: {}", e)),
+        };
+        if let Some(phase) = data.update_phase_seeding.as_mut() {
+            phase.seeds = seeds;
+        }
+    }
+    return response;
+}
+
+/// Update the stations and waves of phase groups.
+///
+/// Returns the updated phase groups (see GGData::update_phase_groups()).
+pub async fn update_phase_groups(
+    group_configs: Vec<PhaseGroupUpdateInput>,
+    token: &str,
+) -> GGResponse {
+
+    let query = r#"
+    mutation UpdatePhaseGroups($groupConfigs: [PhaseGroupUpdateInput]!) {
+        updatePhaseGroups(groupConfigs: $groupConfigs) {
+            id
+            displayIdentifier
+            startAt
+            wave {
+                id
+                identifier
+                startAt
+            }
+        }
+    }
+    "#;
+
+    let vars = PhaseVars { phase_id: None, seed_mapping: None, group_configs: Some(group_configs) };
+
+    return execute_mutation(token, query, vars).await;
+}
+
+/// Variables for registering for a tournament.
+#[derive(Clone, Serialize)]
+struct RegistrationVars {
+    id: GGID,
+    registration: Option<TournamentRegistrationInput>,
+
+    #[serde(rename(serialize = "userId"))]
+    user_id: Option<GGID>,
+
+    #[serde(rename(serialize = "registrationToken"))]
+    registration_token: Option<String>,
+}
+
+/// Returns an error if registration for the tournament is closed.
+///
+/// Registration is closed if it isn't open or if the time registration closes at has passed.
+fn check_registration_window(
+    tournament: &GGTournament,
+    now: DateTime<Utc>,
+) -> Result<(), GGRegistrationError> {
+    if !tournament.is_registration_open() || (tournament.registration_closes_at.is_some() && tournament.registration_closes_at() <= now) {
+        let closes_at = tournament.registration_closes_at.map(|_| tournament.registration_closes_at());
+        return Err(GGRegistrationError::RegistrationClosed(closes_at));
+    }
+    return Ok(());
+}
+
+/// Fetches the registration window of a tournament and returns an error if it is closed.
+async fn check_registration_open(
+    tournament_id: &GGID,
+    token: &str,
+) -> Result<(), GGRegistrationError> {
+
+    let query = r#"
+    query RegistrationWindow($id: ID!) {
+        tournament(id: $id) {
+            id
+            isRegistrationOpen
+            registrationClosesAt
+        }
+    }
+    "#;
+
+    let vars = RegistrationVars { id: tournament_id.clone(), registration: None, user_id: None, registration_token: None };
+
+    match execute_query(token, query, vars).await {
+        GGResponse::Data(data) => match &data.tournament {
+            Some(tournament) => return check_registration_window(tournament, Utc::now()),
+            None => return Err(GGRegistrationError::Error(format!("Tournament {} was not found", tournament_id))),
+        },
+        GGResponse::Error(e) => return Err(GGRegistrationError::Error(e)),
+    }
+}
+
+/// Generate a registration token for a user.
+///
+/// The token can then be used by the user to register through register_for_tournament().
+/// Returns GGRegistrationError::RegistrationClosed without making the mutation if registration for the tournament is closed.
+pub async fn generate_registration_token(
+    tournament_id: GGID,
+    registration: TournamentRegistrationInput,
+    user_id: GGID,
+    token: &str,
+) -> Result<String, GGRegistrationError> {
+
+    check_registration_open(&tournament_id, token).await?;
+
+    let query = r#"
+    mutation GenerateRegistrationToken($registration: TournamentRegistrationInput!, $userId: ID!) {
+        generateRegistrationToken(registration: $registration, userId: $userId)
+    }
+    "#;
+
+    let vars = RegistrationVars { id: tournament_id, registration: Some(registration), user_id: Some(user_id), registration_token: None };
+
+    match execute_query(token, query, vars).await {
+        GGResponse::Data(data) => return Ok(data.generate_registration_token()),
+        GGResponse::Error(e) => return Err(GGRegistrationError::Error(e)),
+    }
+}
+
+/// Register for a tournament.
+///
+/// Registers the owner of the token, or the user a registration token was generated for.
+/// Unlike the other mutations, registering doesn't need permission to manage the tournament, so errors are returned as they are.
+/// Returns GGRegistrationError::RegistrationClosed without making the mutation if registration for the tournament is closed.
+pub async fn register_for_tournament(
+    tournament_id: GGID,
+    registration: TournamentRegistrationInput,
+    registration_token: Option<String>,
+    token: &str,
+) -> Result<GGParticipant, GGRegistrationError> {
+
+    check_registration_open(&tournament_id, token).await?;
+
+    let query = r#"
+    mutation RegisterForTournament($registration: TournamentRegistrationInput, $registrationToken: String) {
+        registerForTournament(registration: $registration, registrationToken: $registrationToken) {
+            id
+            gamerTag
+            prefix
+            entrants {
+                id
+                name
+                event {
+                    id
+                    name
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = RegistrationVars { id: tournament_id, registration: Some(registration), user_id: None, registration_token: registration_token };
+
+    match execute_query(token, query, vars).await {
+        GGResponse::Data(data) => return Ok(data.register_for_tournament()),
+        GGResponse::Error(e) => return Err(GGRegistrationError::Error(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_errors_are_detected() {
+        assert!(is_permission_error("You do not have permission to do that."));
+        assert!(is_permission_error("The response is [403]: Forbidden"));
+        assert!(is_permission_error("Unauthorized"));
+        assert!(!is_permission_error("Set not found"));
+    }
+
+    #[test]
+    fn seed_mapping_must_be_a_permutation() {
+        let seeds = |nums: &[(i64, i64)]| -> Vec<(GGID, i64)> {
+            nums.iter().map(|(id, num)| (GGID::Int(*id), *num)).collect()
+        };
+
+        assert!(validate_seed_mapping(&seeds(&[(10, 2), (11, 1), (12, 3)])).is_ok());
+        assert!(validate_seed_mapping(&seeds(&[(10, 1), (11, 1), (12, 3)])).is_err());
+        assert!(validate_seed_mapping(&seeds(&[(10, 1), (11, 2), (12, 4)])).is_err());
+        assert!(validate_seed_mapping(&seeds(&[(10, 1), (10, 2)])).is_err());
+        assert!(validate_seed_mapping(&seeds(&[(10, 0)])).is_err());
+        assert!(validate_seed_mapping(&[]).is_err());
+    }
+
+    #[test]
+    fn registration_window_is_checked() {
+        use chrono::TimeZone;
+
+        let now = Utc.timestamp_opt(2000, 0).unwrap();
+        let tournament = |open: bool, closes_at: Option<i64>| GGTournament {
+            is_registration_open: Some(open),
+            registration_closes_at: closes_at,
+            ..Default::default()
+        };
+
+        assert!(check_registration_window(&tournament(true, Some(3000)), now).is_ok());
+        assert!(check_registration_window(&tournament(true, None), now).is_ok());
+        assert!(check_registration_window(&tournament(false, Some(3000)), now).is_err());
+        assert!(check_registration_window(&tournament(true, Some(1000)), now)
+            == Err(GGRegistrationError::RegistrationClosed(Some(Utc.timestamp_opt(1000, 0).unwrap()))));
+        assert!(check_registration_window(&tournament(false, None), now) == Err(GGRegistrationError::RegistrationClosed(None)));
+    }
+}
@@ -0,0 +1,515 @@
+//! Standings tables for round robin and Swiss pools, computed from the pool's sets.
+//!
+//! The table counts set and game records for each entrant and orders them by the pool's tiebreakers.
+//! It can be checked against the standings start.gg calculates, and Swiss pools can preview the pairings of the next round.
+
+use serde_json::Value;
+
+use std::collections::{
+    hash_map::Entry,
+    HashMap,
+    HashSet,
+};
+
+use crate::{
+    enums::*,
+    execute_paginated_query,
+    structs::*,
+    Vars,
+};
+
+/// A way of ordering entrants with the same record.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tiebreaker {
+
+    /// The most sets won.
+    SetWins,
+
+    /// The most sets won minus sets lost.
+    SetDifferential,
+
+    /// The most games won.
+    GameWins,
+
+    /// The most games won minus games lost.
+    GameDifferential,
+
+    /// The most sets won against the other tied entrants.
+    HeadToHead,
+
+    /// The best initial seed.
+    Seed,
+
+}
+
+impl Tiebreaker {
+
+    /// Returns the tiebreakers used when a pool doesn't have its own order.
+    pub fn default_order() -> Vec<Tiebreaker> {
+        return vec![Tiebreaker::SetWins, Tiebreaker::HeadToHead, Tiebreaker::GameDifferential, Tiebreaker::Seed];
+    }
+
+    /// Reads the tiebreakers from a phase group's tiebreaker order.
+    ///
+    /// Every name in the JSON is read in order, whether the entries are names or objects, and names that aren't recognized are skipped.
+    /// Set wins always come first, as pools are ranked by record before any tiebreaker. Returns the default order if no tiebreakers are recognized.
+    pub fn parse_order(order: &Value) -> Vec<Tiebreaker> {
+        let mut names: Vec<String> = Vec::new();
+        collect_strings(order, &mut names);
+
+        let mut result: Vec<Tiebreaker> = vec![Tiebreaker::SetWins];
+        for name in names {
+            let name: String = name.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+            let tiebreaker = if name.contains("headtohead") || name.contains("h2h") {
+                Tiebreaker::HeadToHead
+            } else if name.contains("game") && name.contains("diff") {
+                Tiebreaker::GameDifferential
+            } else if name.contains("game") {
+                Tiebreaker::GameWins
+            } else if (name.contains("set") || name.contains("match")) && name.contains("diff") {
+                Tiebreaker::SetDifferential
+            } else if name.contains("seed") {
+                Tiebreaker::Seed
+            } else if name.contains("win") {
+                Tiebreaker::SetWins
+            } else {
+                continue;
+            };
+            if !result.contains(&tiebreaker) {
+                result.push(tiebreaker);
+            }
+        }
+        if result.len() == 1 {
+            return Tiebreaker::default_order();
+        }
+        return result;
+    }
+
+}
+
+/// Adds every string in a JSON value to a list, in order.
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(string) => strings.push(string.clone()),
+        Value::Array(values) => values.iter().for_each(|value| collect_strings(value, strings)),
+        Value::Object(object) => object.values().for_each(|value| collect_strings(value, strings)),
+        _ => {},
+    }
+}
+
+/// An entrant's record in a pool.
+#[derive(Clone)]
+pub struct PoolRecord {
+
+    pub entrant:        GGEntrant,
+
+    /// The entrant's rank in the pool. Entrants that can't be separated by the tiebreakers share a rank.
+    pub rank:           i64,
+    pub set_wins:       i64,
+    pub set_losses:     i64,
+    pub game_wins:      i64,
+    pub game_losses:    i64,
+
+    /// The number of sets the entrant played without an opponent.
+    pub byes:           i64,
+
+}
+
+impl PoolRecord {
+
+    /// Returns the sets won minus the sets lost.
+    pub fn set_differential(&self) -> i64 {
+        return self.set_wins - self.set_losses;
+    }
+
+    /// Returns the games won minus the games lost.
+    pub fn game_differential(&self) -> i64 {
+        return self.game_wins - self.game_losses;
+    }
+
+}
+
+/// A difference between the computed rank of an entrant and start.gg's placement.
+#[derive(Clone)]
+pub struct PoolMismatch {
+
+    pub entrant:        GGEntrant,
+    pub computed:       i64,
+    pub server:         i64,
+
+}
+
+/// The pairings of the next round of a Swiss pool.
+#[derive(Clone, Default)]
+pub struct SwissPairings {
+
+    pub pairs:          Vec<(GGEntrant, GGEntrant)>,
+
+    /// The entrant left without an opponent when there is an odd number of entrants.
+    pub bye:            Option<GGEntrant>,
+
+}
+
+/// The standings table of a round robin or Swiss pool.
+#[derive(Clone, Default)]
+pub struct PoolTable {
+
+    records:        Vec<PoolRecord>,
+    tiebreakers:    Vec<Tiebreaker>,
+
+    /// The number of sets each entrant won against each opponent, by entrant id.
+    results:        HashMap<(String, String), i64>,
+
+}
+
+impl PoolTable {
+
+    /// Computes the table from a pool's sets.
+    ///
+    /// Only completed sets count towards records. Games are counted from each slot's score when it was queried, otherwise from the winner of each game.
+    pub fn new(sets: &[GGSet], tiebreakers: Vec<Tiebreaker>) -> PoolTable {
+        let mut records: Vec<PoolRecord> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut results: HashMap<(String, String), i64> = HashMap::new();
+
+        for set in sets {
+            let entrants: Vec<(GGEntrant, Option<f64>)> = set.slots().iter()
+                .filter(|slot| slot.entrant.is_some())
+                .map(|slot| (slot.entrant(), slot.standing.as_ref().and_then(|standing| standing.stats().score().value)))
+                .collect();
+            for (entrant, _) in &entrants {
+                if let Entry::Vacant(entry) = indices.entry(entrant.id().to_string()) {
+                    entry.insert(records.len());
+                    records.push(PoolRecord { entrant: entrant.clone(), rank: 0, set_wins: 0, set_losses: 0, game_wins: 0, game_losses: 0, byes: 0 });
+                }
+            }
+            if entrants.len() == 1 {
+                records[indices[&entrants[0].0.id().to_string()]].byes += 1;
+            }
+            if entrants.len() != 2 || set.state() != GGActivityState::Completed as i64 || set.winner_id.is_none() {
+                continue;
+            }
+
+            let is_dq = set.display_score() == "DQ";
+            for (index, (entrant, score)) in entrants.iter().enumerate() {
+                let (opponent, opponent_score) = &entrants[1 - index];
+                let won = entrant.id().to_string() == set.winner_id().to_string();
+                let games = |id: &GGID, score: &Option<f64>| match score {
+                    Some(score) => score.max(0.0) as i64,
+                    None => set.games().iter().filter(|game| game.winner_id().to_string() == id.to_string()).count() as i64,
+                };
+                let record = &mut records[indices[&entrant.id().to_string()]];
+                if won {
+                    record.set_wins += 1;
+                    *results.entry((entrant.id().to_string(), opponent.id().to_string())).or_insert(0) += 1;
+                } else {
+                    record.set_losses += 1;
+                }
+                if !is_dq {
+                    record.game_wins += games(&entrant.id(), score);
+                    record.game_losses += games(&opponent.id(), opponent_score);
+                }
+            }
+        }
+
+        let mut table = PoolTable { records: records, tiebreakers: tiebreakers, results: results };
+        table.rank();
+        return table;
+    }
+
+    /// Computes the table of a phase group, using its tiebreaker order.
+    ///
+    /// The phase group's sets need their slots, state and winner, see PoolTable::new().
+    pub fn from_phase_group(phase_group: &GGPhaseGroup) -> PoolTable {
+        return PoolTable::new(&phase_group.sets(), Tiebreaker::parse_order(&phase_group.tiebreaker_order()));
+    }
+
+    /// Fetches a round robin or Swiss phase group and computes its table.
+    ///
+    /// Returns an error if the query failed or the phase group isn't a round robin or Swiss pool.
+    pub async fn load(phase_group_id: GGID, token: &str) -> Result<PoolTable, String> {
+
+        let query = r#"
+        query PoolSets($id: ID!, $page: Int!, $per_page: Int!) {
+            phaseGroup(id: $id) {
+                id
+                bracketType
+                tiebreakerOrder
+                sets(page: $page, perPage: $per_page, sortType: ROUND) {
+                    pageInfo {
+                        totalPages
+                    }
+                    nodes {
+                        id
+                        round
+                        state
+                        winnerId
+                        displayScore
+                        slots {
+                            entrant {
+                                id
+                                name
+                                initialSeedNum
+                            }
+                            standing {
+                                stats {
+                                    score {
+                                        value
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        "#;
+
+        let vars = |page| Vars { id: phase_group_id.clone(), slug: "".to_string(), page: page, per_page: 50 };
+
+        let response = execute_paginated_query(token, query, vars, |data| {
+            let sets = data.phase_group.as_mut()?.sets.as_mut()?;
+            Some((sets.page_info().total_pages(), &mut sets.nodes))
+        }).await;
+        match response {
+            GGResponse::Data(data) => {
+                let phase_group = data.phase_group();
+                match GGBracketType::try_from(phase_group.bracket_type()) {
+                    Ok(GGBracketType::RoundRobin) | Ok(GGBracketType::Swiss) => return Ok(PoolTable::from_phase_group(&phase_group)),
+                    _ => return Err(format!("Phase group {} is not a round robin or Swiss pool", phase_group_id)),
+                }
+            },
+            GGResponse::Error(e) => return Err(e),
+        }
+    }
+
+    /// Returns the records of every entrant, ordered by rank.
+    pub fn records(&self) -> &[PoolRecord] {
+        return &self.records;
+    }
+
+    /// Returns the tiebreakers the table is ordered by.
+    pub fn tiebreakers(&self) -> &[Tiebreaker] {
+        return &self.tiebreakers;
+    }
+
+    /// Returns the record of an entrant.
+    pub fn record(&self, entrant_id: &GGID) -> Option<&PoolRecord> {
+        return self.records.iter().find(|record| record.entrant.id().to_string() == entrant_id.to_string());
+    }
+
+    /// Returns the number of sets each entrant won against the other.
+    pub fn head_to_head(&self, entrant_id: &GGID, opponent_id: &GGID) -> (i64, i64) {
+        let (entrant, opponent) = (entrant_id.to_string(), opponent_id.to_string());
+        return (
+            self.results.get(&(entrant.clone(), opponent.clone())).cloned().unwrap_or(0),
+            self.results.get(&(opponent, entrant)).cloned().unwrap_or(0),
+        );
+    }
+
+    /// Compares the computed ranks with the placements start.gg calculated, see get_phase_group_standings().
+    ///
+    /// Returns the entrants whose rank differs from their placement. Entrants missing from either are skipped.
+    pub fn check_standings(&self, standings: &[GGStanding]) -> Vec<PoolMismatch> {
+        let mut mismatches: Vec<PoolMismatch> = Vec::new();
+        for standing in standings {
+            if standing.entrant.is_none() {
+                continue;
+            }
+            if let Some(record) = self.record(&standing.entrant().id()) {
+                if record.rank != standing.placement() {
+                    mismatches.push(PoolMismatch { entrant: record.entrant.clone(), computed: record.rank, server: standing.placement() });
+                }
+            }
+        }
+        return mismatches;
+    }
+
+    /// Previews the pairings of the next round of a Swiss pool.
+    ///
+    /// Entrants are paired in rank order, each with the best ranked entrant below them that they haven't played yet, and with the next entrant if they have played everyone.
+    /// When there is an odd number of entrants, the lowest ranked entrant with the fewest byes gets a bye.
+    pub fn swiss_pairings(&self) -> SwissPairings {
+        let mut entrants: Vec<GGEntrant> = self.records.iter().map(|record| record.entrant.clone()).collect();
+        let mut pairings: SwissPairings = Default::default();
+        if entrants.len() % 2 == 1 {
+            // Searched from the bottom so the lowest ranked entrant wins ties on byes.
+            if let Some(index) = (0..entrants.len()).rev().min_by_key(|index| self.records[*index].byes) {
+                pairings.bye = Some(entrants.remove(index));
+            }
+        }
+
+        let mut paired: HashSet<usize> = HashSet::new();
+        for i in 0..entrants.len() {
+            if paired.contains(&i) {
+                continue;
+            }
+            let unpaired: Vec<usize> = (i + 1..entrants.len()).filter(|j| !paired.contains(j)).collect();
+            let played = |j: &usize| {
+                let (won, lost) = self.head_to_head(&entrants[i].id(), &entrants[*j].id());
+                won + lost > 0
+            };
+            if let Some(j) = unpaired.iter().find(|j| !played(j)).or(unpaired.first()).cloned() {
+                paired.insert(i);
+                paired.insert(j);
+                pairings.pairs.push((entrants[i].clone(), entrants[j].clone()));
+            }
+        }
+        return pairings;
+    }
+
+    /// Orders the records by the tiebreakers and assigns ranks.
+    ///
+    /// Each tiebreaker splits the groups of entrants still tied, so head to head only counts sets between the entrants of a group.
+    fn rank(&mut self) {
+        self.records.sort_by_key(|record| record.entrant.id().to_string());
+        let mut groups: Vec<Vec<PoolRecord>> = vec![std::mem::take(&mut self.records)];
+        for tiebreaker in self.tiebreakers.clone() {
+            let mut split: Vec<Vec<PoolRecord>> = Vec::new();
+            for mut group in groups {
+                if group.len() < 2 {
+                    split.push(group);
+                    continue;
+                }
+                let ids: Vec<String> = group.iter().map(|record| record.entrant.id().to_string()).collect();
+                let key = |record: &PoolRecord| -> i64 {
+                    match tiebreaker {
+                        Tiebreaker::SetWins => return record.set_wins,
+                        Tiebreaker::SetDifferential => return record.set_differential(),
+                        Tiebreaker::GameWins => return record.game_wins,
+                        Tiebreaker::GameDifferential => return record.game_differential(),
+                        Tiebreaker::HeadToHead => {
+                            let id = record.entrant.id().to_string();
+                            return ids.iter().map(|opponent| self.results.get(&(id.clone(), opponent.clone())).cloned().unwrap_or(0)).sum();
+                        },
+                        // Lower seeds are better, and entrants without a seed come last.
+                        Tiebreaker::Seed => return -record.entrant.initial_seed_num().checked_sub(1).filter(|seed| *seed >= 0).unwrap_or(i64::MAX - 1),
+                    }
+                };
+                group.sort_by_key(|record| -key(record));
+                let mut current: Vec<PoolRecord> = Vec::new();
+                for record in group {
+                    if current.last().is_some_and(|last| key(last) != key(&record)) {
+                        split.push(std::mem::take(&mut current));
+                    }
+                    current.push(record);
+                }
+                split.push(current);
+            }
+            groups = split;
+        }
+
+        for group in groups {
+            let rank = self.records.len() as i64 + 1;
+            for mut record in group {
+                record.rank = rank;
+                self.records.push(record);
+            }
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn set(a: i64, b: i64, score: (f64, f64), winner_id: Option<i64>) -> GGSet {
+        let slot = |id: i64, score: f64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(id)), initial_seed_num: Some(id), ..Default::default() })),
+            standing: Some(Box::new(GGStanding {
+                stats: Some(Box::new(GGStandingStats { score: Some(Box::new(GGScore { value: Some(score), ..Default::default() })) })),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        return GGSet {
+            state: Some(if winner_id.is_some() { GGActivityState::Completed } else { GGActivityState::Created } as i64),
+            winner_id: winner_id,
+            slots: Some(vec![slot(a, score.0), slot(b, score.1)]),
+            ..Default::default()
+        };
+    }
+
+    fn bye(a: i64) -> GGSet {
+        return GGSet {
+            slots: Some(vec![
+                GGSetSlot { entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(a)), initial_seed_num: Some(a), ..Default::default() })), ..Default::default() },
+                GGSetSlot { ..Default::default() },
+            ]),
+            ..Default::default()
+        };
+    }
+
+    fn order(table: &PoolTable) -> Vec<(String, i64)> {
+        return table.records().iter().map(|record| (record.entrant.id().to_string(), record.rank)).collect();
+    }
+
+    #[test]
+    fn circular_ties_fall_through_head_to_head() {
+        // Everyone finishes 1-1 and beats one other entrant, so game differential decides.
+        let sets = vec![set(1, 2, (2.0, 0.0), Some(1)), set(2, 3, (2.0, 1.0), Some(2)), set(3, 1, (2.0, 1.0), Some(3))];
+        let table = PoolTable::new(&sets, Tiebreaker::default_order());
+
+        assert_eq!(order(&table), vec![("1".to_string(), 1), ("3".to_string(), 2), ("2".to_string(), 3)]);
+        assert_eq!(table.record(&GGID::Int(1)).unwrap().game_differential(), 1);
+        assert_eq!(table.head_to_head(&GGID::Int(3), &GGID::Int(1)), (1, 0));
+    }
+
+    #[test]
+    fn head_to_head_breaks_two_way_ties() {
+        let sets = vec![
+            set(1, 2, (2.0, 1.0), Some(2)),
+            set(1, 3, (2.0, 0.0), Some(1)),
+            set(2, 3, (0.0, 2.0), Some(3)),
+            set(1, 4, (2.0, 0.0), Some(1)),
+            set(2, 4, (2.0, 0.0), Some(2)),
+            set(3, 4, (0.0, 2.0), Some(4)),
+        ];
+        let table = PoolTable::new(&sets, Tiebreaker::default_order());
+
+        // Entrants 1 and 2 are both 2-1, and entrant 2 won their set.
+        assert_eq!(order(&table)[..2], [("2".to_string(), 1), ("1".to_string(), 2)]);
+
+        let standings = vec![GGStanding { placement: Some(2), entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(2)), ..Default::default() })), ..Default::default() }];
+        let mismatches = table.check_standings(&standings);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!((mismatches[0].computed, mismatches[0].server), (1, 2));
+    }
+
+    #[test]
+    fn tiebreaker_order_is_parsed() {
+        let order = Tiebreaker::parse_order(&json!(["GAME_DIFFERENTIAL", { "type": "headToHead" }, "unknown"]));
+        assert!(order == vec![Tiebreaker::SetWins, Tiebreaker::GameDifferential, Tiebreaker::HeadToHead]);
+        assert!(Tiebreaker::parse_order(&Value::Null) == Tiebreaker::default_order());
+    }
+
+    #[test]
+    fn swiss_pairings_avoid_rematches() {
+        let sets = vec![set(1, 2, (2.0, 0.0), Some(1)), set(3, 4, (2.0, 0.0), Some(3)), set(5, 6, (2.0, 0.0), Some(5))];
+        let table = PoolTable::new(&sets, Tiebreaker::default_order());
+        let pairings = table.swiss_pairings();
+
+        let pairs: Vec<(String, String)> = pairings.pairs.iter().map(|(a, b)| (a.id().to_string(), b.id().to_string())).collect();
+        assert_eq!(pairs, vec![("1".to_string(), "3".to_string()), ("5".to_string(), "2".to_string()), ("4".to_string(), "6".to_string())]);
+        assert!(pairings.bye.is_none());
+    }
+
+    #[test]
+    fn swiss_byes_go_to_entrants_without_one() {
+        let mut sets = vec![set(1, 2, (2.0, 0.0), Some(1)), set(3, 4, (2.0, 0.0), Some(3)), bye(5)];
+        let pairings = PoolTable::new(&sets, Tiebreaker::default_order()).swiss_pairings();
+        assert_eq!(pairings.bye.unwrap().id().to_string(), "4");
+
+        // Entrant 5 loses the second round and drops to the bottom with entrant 4, but both have already had a bye.
+        sets.extend(vec![set(1, 3, (2.0, 0.0), Some(1)), set(5, 2, (0.0, 2.0), Some(2)), bye(4)]);
+        let table = PoolTable::new(&sets, Tiebreaker::default_order());
+        assert_eq!(order(&table).last().unwrap().0, "5");
+        assert_eq!(table.record(&GGID::Int(5)).unwrap().byes, 1);
+
+        let pairings = table.swiss_pairings();
+        assert_eq!(pairings.bye.unwrap().id().to_string(), "3");
+        assert_eq!(pairings.pairs.len(), 2);
+    }
+}
@@ -0,0 +1,438 @@
+//! Projection of the possible results of a bracket, used to find the placements each entrant can still reach and how likely they are.
+//!
+//! The unfinished sets of a bracket are either enumerated, when there are few enough of them, or sampled.
+//! The chance of each entrant winning a set comes from a win probability model, which uses the entrants' seeds by default.
+
+use std::collections::HashMap;
+
+use crate::{
+    bracket::*,
+    enums::*,
+    structs::*,
+};
+
+/// A model of how likely an entrant is to win a set.
+///
+/// Any function taking the two entrants and returning the chance of the first winning can be used as a model.
+pub trait WinProbability {
+
+    /// Returns the chance of the entrant beating the opponent, from 0 to 1.
+    fn win_probability(&self, entrant: &GGEntrant, opponent: &GGEntrant) -> f64;
+
+}
+
+impl<F: Fn(&GGEntrant, &GGEntrant) -> f64> WinProbability for F {
+    fn win_probability(&self, entrant: &GGEntrant, opponent: &GGEntrant) -> f64 {
+        return self(entrant, opponent);
+    }
+}
+
+/// Win probability based on the entrants' initial seeds.
+///
+/// The chance of beating an opponent is the opponent's seed divided by the sum of both seeds, so seed 1 beats seed 3 three times out of four.
+/// Entrants without a seed are treated as evenly matched.
+#[derive(Clone, Copy, Default)]
+pub struct SeedModel;
+
+impl WinProbability for SeedModel {
+    fn win_probability(&self, entrant: &GGEntrant, opponent: &GGEntrant) -> f64 {
+        let (seed, opponent_seed) = (entrant.initial_seed_num(), opponent.initial_seed_num());
+        if seed <= 0 || opponent_seed <= 0 {
+            return 0.5;
+        }
+        return opponent_seed as f64 / (seed + opponent_seed) as f64;
+    }
+}
+
+/// Options for projecting a bracket.
+#[derive(Clone)]
+pub struct ProjectionOptions {
+
+    /// Every outcome is enumerated when there are at most this many unfinished sets, otherwise outcomes are sampled.
+    pub max_enumerated_sets:    usize,
+
+    /// The number of outcomes sampled.
+    pub samples:                usize,
+
+    /// The seed of the random numbers used for sampling, so projections can be repeated.
+    pub seed:                   u64,
+
+}
+
+impl Default for ProjectionOptions {
+    fn default() -> ProjectionOptions {
+        return ProjectionOptions { max_enumerated_sets: 16, samples: 10000, seed: 0x2545F4914F6CDD1D };
+    }
+}
+
+/// The projected placements of an entrant.
+#[derive(Clone)]
+pub struct EntrantProjection {
+
+    pub entrant:            GGEntrant,
+
+    /// The best placement the entrant can reach.
+    pub best_placement:     i64,
+
+    /// The worst placement the entrant can reach.
+    pub worst_placement:    i64,
+
+    /// The chance of each placement the entrant can reach, sorted by placement.
+    pub probabilities:      Vec<(i64, f64)>,
+
+}
+
+impl EntrantProjection {
+
+    /// Returns the chance of the entrant finishing in a placement.
+    pub fn probability(&self, placement: i64) -> f64 {
+        return self.probabilities.iter().find(|(value, _)| *value == placement).map_or(0.0, |(_, probability)| *probability);
+    }
+
+}
+
+/// Projects the final placements of every entrant in a bracket.
+///
+/// Completed sets keep their result, and the remaining sets are decided by the model.
+/// Placements come from the wPlacement and lPlacement of the set an entrant is eliminated in (or wins last), so they need to be queried, see get_phase_group_sets().
+/// When outcomes are sampled, the best and worst placements are the best and worst that were sampled.
+/// Returns a projection for each entrant, sorted by best placement.
+pub fn project_bracket(
+    bracket: &Bracket,
+    model: &dyn WinProbability,
+    options: &ProjectionOptions,
+) -> Vec<EntrantProjection> {
+
+    let plan = Plan::new(bracket);
+    let mut results: HashMap<String, (GGEntrant, HashMap<i64, f64>)> = HashMap::new();
+    let unfinished = bracket.sets().iter().filter(|set| set.state() != GGActivityState::Completed as i64).count();
+
+    if unfinished <= options.max_enumerated_sets {
+        plan.enumerate(0, vec![None; plan.order.len()], Vec::new(), 1.0, model, &mut results);
+    } else {
+        let mut rng = Rng(options.seed.max(1));
+        let weight = 1.0 / options.samples.max(1) as f64;
+        for _ in 0..options.samples.max(1) {
+            let mut outcomes: Vec<Option<Outcome>> = vec![None; plan.order.len()];
+            let mut placements: Vec<(GGEntrant, i64)> = Vec::new();
+            for &index in &plan.order {
+                let outcome = match plan.resolve(index, &outcomes) {
+                    Resolution::Done(outcome) => outcome,
+                    Resolution::Pending(a, b) => {
+                        let probability = model.win_probability(&a.1, &b.1).clamp(0.0, 1.0);
+                        if rng.next() < probability { plan.finish(index, a, Some(b.1)) } else { plan.finish(index, b, Some(a.1)) }
+                    },
+                };
+                plan.place(index, &outcome, &mut placements);
+                outcomes[index] = Some(outcome);
+            }
+            record(&placements, weight, &mut results);
+        }
+    }
+
+    let mut projections: Vec<EntrantProjection> = results.into_values().map(|(entrant, placements)| {
+        let mut probabilities: Vec<(i64, f64)> = placements.into_iter().collect();
+        probabilities.sort_by_key(|(placement, _)| *placement);
+        EntrantProjection {
+            entrant:            entrant,
+            best_placement:     probabilities.first().map_or(0, |(placement, _)| *placement),
+            worst_placement:    probabilities.last().map_or(0, |(placement, _)| *placement),
+            probabilities:      probabilities,
+        }
+    }).collect();
+    projections.sort_by(|a, b| a.best_placement.cmp(&b.best_placement).then(a.worst_placement.cmp(&b.worst_placement)));
+    return projections;
+}
+
+/// Adds the placements of an outcome to the results.
+fn record(placements: &[(GGEntrant, i64)], weight: f64, results: &mut HashMap<String, (GGEntrant, HashMap<i64, f64>)>) {
+    for (entrant, placement) in placements {
+        let result = results.entry(entrant.id().to_string()).or_insert_with(|| (entrant.clone(), HashMap::new()));
+        *result.1.entry(*placement).or_insert(0.0) += weight;
+    }
+}
+
+/// Where the entrant in a slot comes from.
+enum SlotSource {
+
+    Fixed(Option<GGEntrant>),
+    From(usize, Progression),
+
+}
+
+/// The result of a set in one outcome.
+#[derive(Clone)]
+struct Outcome {
+    winner:     Option<GGEntrant>,
+    loser:      Option<GGEntrant>,
+
+    /// False when the entrants don't move on, such as when the winners side entrant wins the grand finals and there is no reset.
+    forward:    bool,
+}
+
+/// A set's result, or the two entrants (with their slot index) left to play it.
+enum Resolution {
+
+    Done(Outcome),
+    Pending((usize, GGEntrant), (usize, GGEntrant)),
+
+}
+
+/// The sets of a bracket in the order they are played, with where each slot's entrant comes from.
+struct Plan<'a> {
+    bracket:        &'a Bracket,
+    order:          Vec<usize>,
+    slots:          Vec<Vec<SlotSource>>,
+    next_winner:    Vec<bool>,
+    next_loser:     Vec<bool>,
+
+    /// The grand finals and the index of its winners side slot, when the bracket has a reset.
+    reset:          Option<(usize, usize)>,
+}
+
+impl<'a> Plan<'a> {
+
+    fn new(bracket: &'a Bracket) -> Plan<'a> {
+        let sets = bracket.sets();
+        let index = |id: &GGID| sets.iter().position(|set| set.id() == *id);
+
+        let mut slots: Vec<Vec<SlotSource>> = Vec::new();
+        for set in sets {
+            let edges = bracket.prerequisites(&set.id());
+            slots.push(set.slots().into_iter().enumerate().map(|(slot_index, slot)| {
+                if slot.entrant.is_some() {
+                    return SlotSource::Fixed(Some(slot.entrant()));
+                }
+                match edges.iter().find(|edge| edge.slot_index == slot_index).and_then(|edge| Some((index(&edge.from)?, edge.progression))) {
+                    Some((from, progression)) => SlotSource::From(from, progression),
+                    None => SlotSource::Fixed(None),
+                }
+            }).collect());
+        }
+
+        // Sets are ordered so each one comes after the sets feeding into it.
+        let mut remaining: Vec<usize> = sets.iter().map(|set| bracket.prerequisites(&set.id()).len()).collect();
+        let mut order: Vec<usize> = (0..sets.len()).filter(|i| remaining[*i] == 0).collect();
+        let mut position = 0;
+        while position < order.len() {
+            let id = sets[order[position]].id();
+            for edge in bracket.edges().iter().filter(|edge| edge.from == id) {
+                if let Some(to) = index(&edge.to) {
+                    remaining[to] -= 1;
+                    if remaining[to] == 0 {
+                        order.push(to);
+                    }
+                }
+            }
+            position += 1;
+        }
+
+        let reset = bracket.grand_finals_reset().and_then(|_| {
+            let grand_finals = bracket.grand_finals()?;
+            let winners_side = bracket.prerequisites(&grand_finals.id()).into_iter()
+                .find(|edge| edge.progression == Progression::Winner && bracket.set(&edge.from).is_some_and(|set| set.round() > 0))?;
+            Some((index(&grand_finals.id())?, winners_side.slot_index))
+        });
+
+        return Plan {
+            bracket:        bracket,
+            order:          order,
+            slots:          slots,
+            next_winner:    sets.iter().map(|set| bracket.next(&set.id(), Progression::Winner).is_some()).collect(),
+            next_loser:     sets.iter().map(|set| bracket.next(&set.id(), Progression::Loser).is_some()).collect(),
+            reset:          reset,
+        };
+    }
+
+    /// Returns the result of a set if it is decided, or the entrants left to play it.
+    fn resolve(&self, index: usize, outcomes: &[Option<Outcome>]) -> Resolution {
+        let entrants: Vec<(usize, GGEntrant)> = self.slots[index].iter().enumerate().filter_map(|(slot_index, source)| {
+            let entrant = match source {
+                SlotSource::Fixed(entrant) => entrant.clone(),
+                SlotSource::From(from, progression) => outcomes[*from].as_ref().filter(|outcome| outcome.forward).and_then(|outcome| match progression {
+                    Progression::Winner => outcome.winner.clone(),
+                    Progression::Loser => outcome.loser.clone(),
+                }),
+            };
+            entrant.map(|entrant| (slot_index, entrant))
+        }).collect();
+
+        let set = &self.bracket.sets()[index];
+        if set.state() == GGActivityState::Completed as i64 {
+            let winner_id = set.winner_id().to_string();
+            if let Some(winner) = entrants.iter().find(|(_, entrant)| entrant.id().to_string() == winner_id) {
+                let loser = entrants.iter().find(|(_, entrant)| entrant.id().to_string() != winner_id).map(|(_, entrant)| entrant.clone());
+                return Resolution::Done(self.finish(index, winner.clone(), loser));
+            }
+        }
+        match entrants.len() {
+            0 => return Resolution::Done(Outcome { winner: None, loser: None, forward: true }),
+            1 => return Resolution::Done(self.finish(index, entrants[0].clone(), None)),
+            _ => return Resolution::Pending(entrants[0].clone(), entrants[1].clone()),
+        }
+    }
+
+    /// Returns the outcome of a set won by the entrant in a slot.
+    fn finish(&self, index: usize, winner: (usize, GGEntrant), loser: Option<GGEntrant>) -> Outcome {
+        let forward = self.reset != Some((index, winner.0));
+        return Outcome { winner: Some(winner.1), loser: loser, forward: forward };
+    }
+
+    /// Adds the placements of the entrants eliminated in (or winning) a set.
+    fn place(&self, index: usize, outcome: &Outcome, placements: &mut Vec<(GGEntrant, i64)>) {
+        let set = &self.bracket.sets()[index];
+        if let Some(loser) = &outcome.loser {
+            if (!self.next_loser[index] || !outcome.forward) && set.l_placement() > 0 {
+                placements.push((loser.clone(), set.l_placement()));
+            }
+        }
+        if let Some(winner) = &outcome.winner {
+            if (!self.next_winner[index] || !outcome.forward) && set.w_placement() > 0 {
+                placements.push((winner.clone(), set.w_placement()));
+            }
+        }
+    }
+
+    /// Enumerates every outcome of the sets from a position in the order.
+    fn enumerate(
+        &self,
+        position: usize,
+        mut outcomes: Vec<Option<Outcome>>,
+        mut placements: Vec<(GGEntrant, i64)>,
+        weight: f64,
+        model: &dyn WinProbability,
+        results: &mut HashMap<String, (GGEntrant, HashMap<i64, f64>)>,
+    ) {
+        if position == self.order.len() {
+            record(&placements, weight, results);
+            return;
+        }
+        let index = self.order[position];
+        match self.resolve(index, &outcomes) {
+            Resolution::Done(outcome) => {
+                self.place(index, &outcome, &mut placements);
+                outcomes[index] = Some(outcome);
+                self.enumerate(position + 1, outcomes, placements, weight, model, results);
+            },
+            Resolution::Pending(a, b) => {
+                let probability = model.win_probability(&a.1, &b.1).clamp(0.0, 1.0);
+                for (winner, loser, chance) in [(a.clone(), b.1.clone(), probability), (b, a.1, 1.0 - probability)] {
+                    if chance <= 0.0 {
+                        continue;
+                    }
+                    let outcome = self.finish(index, winner, Some(loser));
+                    let mut placements = placements.clone();
+                    let mut outcomes = outcomes.clone();
+                    self.place(index, &outcome, &mut placements);
+                    outcomes[index] = Some(outcome);
+                    self.enumerate(position + 1, outcomes, placements, weight * chance, model, results);
+                }
+            },
+        }
+    }
+
+}
+
+/// A small xorshift random number generator, so sampling doesn't need another dependency.
+struct Rng(u64);
+
+impl Rng {
+
+    /// Returns a random number from 0 to 1.
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return (self.0 >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(id: i64) -> GGEntrant {
+        return GGEntrant { id: Some(GGID::Int(id)), initial_seed_num: Some(id / 100), ..Default::default() };
+    }
+
+    fn slot(prereq: Option<(i64, i64)>, entrant_id: Option<i64>) -> GGSetSlot {
+        return GGSetSlot {
+            prereq_id: prereq.map(|(id, _)| id.to_string()),
+            prereq_type: Some(if prereq.is_some() { "set" } else { "seed" }.to_string()),
+            prereq_placement: prereq.map(|(_, placement)| placement),
+            entrant: entrant_id.map(|id| Box::new(entrant(id))),
+            ..Default::default()
+        };
+    }
+
+    fn set(id: i64, round: i64, placements: (i64, i64), winner_id: Option<i64>, slots: Vec<GGSetSlot>) -> GGSet {
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            round: Some(round),
+            state: Some(if winner_id.is_some() { GGActivityState::Completed } else { GGActivityState::Created } as i64),
+            winner_id: winner_id,
+            w_placement: Some(placements.0),
+            l_placement: Some(placements.1),
+            slots: Some(slots),
+            ..Default::default()
+        };
+    }
+
+    fn find(projections: &[EntrantProjection], id: i64) -> &EntrantProjection {
+        return projections.iter().find(|projection| projection.entrant.id() == GGID::Int(id)).unwrap();
+    }
+
+    #[test]
+    fn single_elimination_is_enumerated() {
+        let bracket = Bracket::new(vec![
+            set(1, 1, (0, 3), None, vec![slot(None, Some(100)), slot(None, Some(400))]),
+            set(2, 1, (0, 3), None, vec![slot(None, Some(200)), slot(None, Some(300))]),
+            set(3, 2, (1, 2), None, vec![slot(Some((1, 1)), None), slot(Some((2, 1)), None)]),
+        ]);
+        let projections = project_bracket(&bracket, &SeedModel, &Default::default());
+
+        let top_seed = find(&projections, 100);
+        assert_eq!((top_seed.best_placement, top_seed.worst_placement), (1, 3));
+        // Seed 1 beats seed 4 with a chance of 0.8, then seed 2 or 3 with a chance of 2/3 or 3/4.
+        assert!((top_seed.probability(1) - 0.8 * (0.6 * 2.0 / 3.0 + 0.4 * 0.75)).abs() < 1e-9);
+        for projection in &projections {
+            let total: f64 = projection.probabilities.iter().map(|(_, probability)| probability).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn grand_finals_reset_is_only_played_when_the_losers_side_wins() {
+        let bracket = Bracket::new(vec![
+            set(1, 1, (0, 0), Some(100), vec![slot(None, Some(100)), slot(None, Some(400))]),
+            set(2, 1, (0, 0), Some(200), vec![slot(None, Some(200)), slot(None, Some(300))]),
+            set(3, 2, (0, 0), Some(100), vec![slot(Some((1, 1)), Some(100)), slot(Some((2, 1)), Some(200))]),
+            set(4, -1, (0, 4), Some(300), vec![slot(Some((1, 2)), Some(400)), slot(Some((2, 2)), Some(300))]),
+            set(5, -2, (0, 3), Some(200), vec![slot(Some((3, 2)), Some(200)), slot(Some((4, 1)), Some(300))]),
+            set(6, 3, (1, 2), None, vec![slot(Some((3, 1)), Some(100)), slot(Some((5, 1)), Some(200))]),
+            set(7, 4, (1, 2), None, vec![slot(Some((6, 1)), None), slot(Some((6, 2)), None)]),
+        ]);
+        // Entrant 100 wins each set against entrant 200 with a chance of 0.75.
+        let model = |entrant: &GGEntrant, _: &GGEntrant| if entrant.id() == GGID::Int(100) { 0.75 } else { 0.25 };
+        let projections = project_bracket(&bracket, &model, &Default::default());
+
+        assert!((find(&projections, 100).probability(1) - (0.75 + 0.25 * 0.75)).abs() < 1e-9);
+        assert!((find(&projections, 200).probability(1) - 0.25 * 0.25).abs() < 1e-9);
+        assert_eq!(find(&projections, 300).probabilities, vec![(3, 1.0)]);
+        assert_eq!(find(&projections, 400).probabilities, vec![(4, 1.0)]);
+    }
+
+    #[test]
+    fn sampling_approximates_enumeration() {
+        let bracket = Bracket::new(vec![
+            set(1, 1, (0, 3), None, vec![slot(None, Some(100)), slot(None, Some(400))]),
+            set(2, 1, (0, 3), None, vec![slot(None, Some(200)), slot(None, Some(300))]),
+            set(3, 2, (1, 2), None, vec![slot(Some((1, 1)), None), slot(Some((2, 1)), None)]),
+        ]);
+        let options = ProjectionOptions { max_enumerated_sets: 0, ..Default::default() };
+        let projections = project_bracket(&bracket, &SeedModel, &options);
+
+        assert!((find(&projections, 100).probability(1) - 0.56).abs() < 0.03);
+    }
+}
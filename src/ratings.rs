@@ -0,0 +1,424 @@
+//! Elo and Glicko-2 ratings for players, computed from completed sets.
+//!
+//! Entrants are matched to players through their participants, so only singles sets are rated.
+//! Every rating change is kept, so the history can be exported once the sets are processed.
+
+use chrono::{
+    DateTime,
+    TimeZone,
+    Utc,
+};
+use serde::Serialize;
+
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    f64::consts::PI,
+};
+
+use crate::{
+    enums::*,
+    structs::*,
+};
+
+/// The scale between Glicko-2 and Glicko ratings.
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// How ratings are calculated.
+#[derive(Clone, Copy)]
+pub enum RatingSystem {
+
+    /// Elo, updated after every set with the k-factor.
+    Elo {
+        k_factor: f64,
+    },
+
+    /// Glicko-2, updated once per rating period, with tau constraining the change in volatility.
+    ///
+    /// Each event a call to RatingEngine::process() is given is a rating period.
+    Glicko2 {
+        tau: f64,
+    },
+
+}
+
+impl Default for RatingSystem {
+    fn default() -> Self {
+        return RatingSystem::Glicko2 { tau: 0.5 };
+    }
+}
+
+/// A player's rating.
+///
+/// The deviation and volatility are only used by Glicko-2, and are zero for Elo.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub struct Rating {
+
+    pub rating:         f64,
+    pub deviation:      f64,
+    pub volatility:     f64,
+
+}
+
+/// A change to a player's rating.
+#[derive(Clone, Serialize)]
+pub struct RatingChange {
+
+    pub player_id:      i64,
+
+    /// The event of the set or rating period.
+    pub event_id:       GGID,
+
+    /// The set the change is for. Glicko-2 changes are for a whole rating period, and don't have a set.
+    pub set_id:         Option<GGID>,
+
+    /// The time the set or the last set of the rating period was completed.
+    pub at:             DateTime<Utc>,
+    pub before:         Rating,
+    pub after:          Rating,
+
+}
+
+/// A rated set, with the players of the winning and losing entrants.
+struct RatedSet {
+
+    id:         GGID,
+    event_id:   GGID,
+    at:         DateTime<Utc>,
+    winner:     i64,
+    loser:      i64,
+
+}
+
+/// Ratings for players, built up from the sets given to it.
+#[derive(Clone, Default)]
+pub struct RatingEngine {
+
+    system:     RatingSystem,
+    ratings:    HashMap<i64, Rating>,
+    history:    Vec<RatingChange>,
+    processed:  HashSet<String>,
+
+}
+
+impl RatingEngine {
+
+    /// Creates an engine with no ratings.
+    pub fn new(system: RatingSystem) -> RatingEngine {
+        return RatingEngine { system: system, ..Default::default() };
+    }
+
+    /// Returns the rating new players start with.
+    pub fn initial_rating(&self) -> Rating {
+        match self.system {
+            RatingSystem::Elo { .. } => return Rating { rating: 1500.0, deviation: 0.0, volatility: 0.0 },
+            RatingSystem::Glicko2 { .. } => return Rating { rating: 1500.0, deviation: 350.0, volatility: 0.06 },
+        }
+    }
+
+    /// Updates the ratings with completed sets, in the order they were completed.
+    ///
+    /// The slots of each set need their entrant, participants and player, and the set needs its winner, completion time and event.
+    /// Sets that weren't completed, were won by disqualification, don't have one player on each side, or were already processed are skipped.
+    /// Returns the number of sets that were rated.
+    pub fn process(&mut self, sets: &[GGSet]) -> usize {
+        let mut rated: Vec<RatedSet> = Vec::new();
+        for set in sets {
+            if set.state.is_some() && set.state() != GGActivityState::Completed as i64 {
+                continue;
+            }
+            if set.winner_id.is_none() || set.display_score() == "DQ" || self.processed.contains(&set.id().to_string()) {
+                continue;
+            }
+            let players: Vec<(String, i64)> = set.slots().iter()
+                .filter(|slot| slot.entrant.is_some())
+                .filter_map(|slot| {
+                    let participants = slot.entrant().participants();
+                    match participants.as_slice() {
+                        [participant] if participant.player().id() != 0 => Some((slot.entrant().id().to_string(), participant.player().id())),
+                        _ => None,
+                    }
+                })
+                .collect();
+            if players.len() != 2 || players[0].1 == players[1].1 {
+                continue;
+            }
+            let winner_index = match players.iter().position(|(entrant_id, _)| *entrant_id == set.winner_id().to_string()) {
+                Some(index) => index,
+                None => continue,
+            };
+            self.processed.insert(set.id().to_string());
+            rated.push(RatedSet {
+                id:         set.id(),
+                event_id:   set.event().id(),
+                at:         set.completed_at(),
+                winner:     players[winner_index].1,
+                loser:      players[1 - winner_index].1,
+            });
+        }
+        rated.sort_by_key(|set| set.at);
+
+        let count = rated.len();
+        match self.system {
+            RatingSystem::Elo { k_factor } => {
+                for set in rated {
+                    self.process_elo(set, k_factor);
+                }
+            },
+            RatingSystem::Glicko2 { tau } => {
+                // Events are rated in the order their first set was completed.
+                let mut periods: Vec<(GGID, Vec<RatedSet>)> = Vec::new();
+                for set in rated {
+                    match periods.iter_mut().find(|(event_id, _)| *event_id == set.event_id) {
+                        Some((_, sets)) => sets.push(set),
+                        None => periods.push((set.event_id.clone(), vec![set])),
+                    }
+                }
+                for (event_id, sets) in periods {
+                    self.process_glicko2_period(event_id, sets, tau);
+                }
+            },
+        }
+        return count;
+    }
+
+    /// Returns a player's current rating.
+    ///
+    /// Returns None if the player hasn't been rated.
+    pub fn rating(&self, player_id: i64) -> Option<Rating> {
+        return self.ratings.get(&player_id).cloned();
+    }
+
+    /// Returns every player's current rating, from highest to lowest.
+    pub fn ratings(&self) -> Vec<(i64, Rating)> {
+        let mut result: Vec<(i64, Rating)> = self.ratings.iter().map(|(id, rating)| (*id, *rating)).collect();
+        result.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating).then(a.0.cmp(&b.0)));
+        return result;
+    }
+
+    /// Returns every rating change, in the order they were made.
+    pub fn history(&self) -> &[RatingChange] {
+        return &self.history;
+    }
+
+    /// Returns the rating changes of a player, in the order they were made.
+    pub fn player_history(&self, player_id: i64) -> Vec<&RatingChange> {
+        return self.history.iter().filter(|change| change.player_id == player_id).collect();
+    }
+
+    /// Returns the rating history as CSV, with a header row.
+    ///
+    /// The history can also be serialized with serde, as RatingChange implements Serialize.
+    pub fn history_csv(&self) -> String {
+        let mut result = "player_id,event_id,set_id,at,rating_before,rating_after,deviation_before,deviation_after,volatility_before,volatility_after\n".to_string();
+        for change in &self.history {
+            result += &format!(
+                "{},{},{},{},{:.2},{:.2},{:.2},{:.2},{:.6},{:.6}\n",
+                change.player_id,
+                change.event_id,
+                change.set_id.as_ref().map(|id| id.to_string()).unwrap_or_default(),
+                change.at.to_rfc3339(),
+                change.before.rating,
+                change.after.rating,
+                change.before.deviation,
+                change.after.deviation,
+                change.before.volatility,
+                change.after.volatility,
+            );
+        }
+        return result;
+    }
+
+    /// Updates the ratings of the players of a set with Elo.
+    fn process_elo(&mut self, set: RatedSet, k_factor: f64) {
+        let winner = self.rating(set.winner).unwrap_or(self.initial_rating());
+        let loser = self.rating(set.loser).unwrap_or(self.initial_rating());
+        let expected = 1.0 / (1.0 + 10.0_f64.powf((loser.rating - winner.rating) / 400.0));
+        let change = k_factor * (1.0 - expected);
+
+        for (player_id, before, after) in [(set.winner, winner, winner.rating + change), (set.loser, loser, loser.rating - change)] {
+            let after = Rating { rating: after, ..before };
+            self.ratings.insert(player_id, after);
+            self.history.push(RatingChange {
+                player_id:  player_id,
+                event_id:   set.event_id.clone(),
+                set_id:     Some(set.id.clone()),
+                at:         set.at,
+                before:     before,
+                after:      after,
+            });
+        }
+    }
+
+    /// Updates every rating for a rating period with Glicko-2.
+    ///
+    /// Players who didn't play in the period only have their deviation increased.
+    fn process_glicko2_period(&mut self, event_id: GGID, sets: Vec<RatedSet>, tau: f64) {
+        let mut results: HashMap<i64, Vec<(Rating, f64)>> = HashMap::new();
+        for set in &sets {
+            for player in [set.winner, set.loser] {
+                if !self.ratings.contains_key(&player) {
+                    self.ratings.insert(player, self.initial_rating());
+                }
+            }
+            results.entry(set.winner).or_default().push((self.ratings[&set.loser], 1.0));
+            results.entry(set.loser).or_default().push((self.ratings[&set.winner], 0.0));
+        }
+        let at = sets.last().map(|set| set.at).unwrap_or(Utc.timestamp_opt(0, 0).unwrap());
+
+        let mut players: Vec<i64> = self.ratings.keys().cloned().collect();
+        players.sort();
+        let mut updated: Vec<(i64, Rating)> = Vec::new();
+        for player_id in players {
+            let before = self.ratings[&player_id];
+            match results.get(&player_id) {
+                Some(games) => {
+                    let after = glicko2_update(before, games, tau);
+                    updated.push((player_id, after));
+                    self.history.push(RatingChange {
+                        player_id:  player_id,
+                        event_id:   event_id.clone(),
+                        set_id:     None,
+                        at:         at,
+                        before:     before,
+                        after:      after,
+                    });
+                },
+                None => {
+                    let phi = before.deviation / GLICKO2_SCALE;
+                    let deviation = (phi * phi + before.volatility * before.volatility).sqrt() * GLICKO2_SCALE;
+                    updated.push((player_id, Rating { deviation: deviation.min(350.0), ..before }));
+                },
+            }
+        }
+        // Every player is updated from the ratings at the start of the period.
+        self.ratings.extend(updated);
+    }
+
+}
+
+/// Returns a Glicko-2 rating after a rating period, given the opponents' ratings and the score against each (1 for a win, 0 for a loss).
+fn glicko2_update(rating: Rating, games: &[(Rating, f64)], tau: f64) -> Rating {
+    let mu = (rating.rating - 1500.0) / GLICKO2_SCALE;
+    let phi = rating.deviation / GLICKO2_SCALE;
+    let sigma = rating.volatility;
+    let g = |phi: f64| 1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt();
+
+    let mut v_inverse = 0.0;
+    let mut improvement = 0.0;
+    for (opponent, score) in games {
+        let mu_j = (opponent.rating - 1500.0) / GLICKO2_SCALE;
+        let g_j = g(opponent.deviation / GLICKO2_SCALE);
+        let expected = 1.0 / (1.0 + (-g_j * (mu - mu_j)).exp());
+        v_inverse += g_j * g_j * expected * (1.0 - expected);
+        improvement += g_j * (score - expected);
+    }
+    let v = 1.0 / v_inverse;
+    let delta = v * improvement;
+
+    // The new volatility is found with the Illinois algorithm, as described by Glickman.
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        return ex * (delta * delta - phi * phi - v - ex) / (2.0 * (phi * phi + v + ex).powi(2)) - (x - a) / (tau * tau);
+    };
+    let mut big_a = a;
+    let mut big_b = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            k += 1.0;
+        }
+        a - k * tau
+    };
+    let (mut f_a, mut f_b) = (f(big_a), f(big_b));
+    while (big_b - big_a).abs() > 0.000001 {
+        let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+        let f_c = f(big_c);
+        if f_c * f_b <= 0.0 {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        big_b = big_c;
+        f_b = f_c;
+    }
+    let volatility = (big_a / 2.0).exp();
+
+    let phi_star = (phi * phi + volatility * volatility).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+    let new_mu = mu + new_phi * new_phi * improvement;
+    return Rating { rating: new_mu * GLICKO2_SCALE + 1500.0, deviation: new_phi * GLICKO2_SCALE, volatility: volatility };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(id: i64, event_id: i64, winner: i64, loser: i64, display_score: &str) -> GGSet {
+        let slot = |player_id: i64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant {
+                id: Some(GGID::Int(player_id * 10)),
+                participants: Some(vec![GGParticipant { player: Some(Box::new(GGPlayer { id: Some(player_id), ..Default::default() })), ..Default::default() }]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            winner_id: Some(winner * 10),
+            display_score: Some(display_score.to_string()),
+            completed_at: Some(id),
+            event: Some(Box::new(GGEvent { id: Some(GGID::Int(event_id)), ..Default::default() })),
+            slots: Some(vec![slot(loser), slot(winner)]),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn glicko2_matches_glickman_example() {
+        let rating = Rating { rating: 1500.0, deviation: 200.0, volatility: 0.06 };
+        let games = vec![
+            (Rating { rating: 1400.0, deviation: 30.0, volatility: 0.06 }, 1.0),
+            (Rating { rating: 1550.0, deviation: 100.0, volatility: 0.06 }, 0.0),
+            (Rating { rating: 1700.0, deviation: 300.0, volatility: 0.06 }, 0.0),
+        ];
+        let after = glicko2_update(rating, &games, 0.5);
+        assert!((after.rating - 1464.06).abs() < 0.01);
+        assert!((after.deviation - 151.52).abs() < 0.01);
+        assert!((after.volatility - 0.05999).abs() < 0.00001);
+    }
+
+    #[test]
+    fn elo_skips_disqualifications_and_repeats() {
+        let mut engine = RatingEngine::new(RatingSystem::Elo { k_factor: 32.0 });
+        assert_eq!(engine.process(&[set(1, 1, 1, 2, "3 - 0"), set(2, 1, 3, 1, "DQ")]), 1);
+        assert_eq!(engine.process(&[set(1, 1, 1, 2, "3 - 0")]), 0);
+
+        assert_eq!(engine.rating(1).unwrap().rating, 1516.0);
+        assert_eq!(engine.rating(2).unwrap().rating, 1484.0);
+        assert!(engine.rating(3).is_none());
+        assert_eq!(engine.ratings()[0].0, 1);
+
+        let csv = engine.history_csv();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().nth(1).unwrap().starts_with("1,1,1,1970-01-01T00:00:01+00:00,1500.00,1516.00"));
+    }
+
+    #[test]
+    fn glicko2_rates_each_event_as_a_period() {
+        let mut engine = RatingEngine::new(Default::default());
+        engine.process(&[set(1, 1, 1, 2, "2 - 0"), set(2, 1, 1, 3, "2 - 1"), set(3, 2, 2, 3, "2 - 0")]);
+
+        assert_eq!(engine.player_history(1).len(), 1);
+        assert_eq!(engine.player_history(2).len(), 2);
+        let order: Vec<i64> = engine.ratings().iter().map(|(id, _)| *id).collect();
+        assert_eq!(order, vec![1, 2, 3]);
+        // Player 1 didn't play in the second event, so only their deviation changed.
+        assert!(engine.rating(1).unwrap().deviation > engine.player_history(1)[0].after.deviation);
+    }
+}
@@ -0,0 +1,484 @@
+//! Schedule estimates for a running event, used to predict when rounds and phase groups will finish.
+//!
+//! Set durations are learned from completed sets, by round type. Each phase group's remaining sets are then played out on its share of the stations.
+//! A set starts once the sets before it in the bracket are finished, its entrants aren't playing another set, and a station is free.
+
+use chrono::{
+    DateTime,
+    TimeZone,
+    Utc,
+};
+
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+use crate::{
+    bracket::*,
+    enums::*,
+    execute_query,
+    get_phase_group_sets,
+    inputs::*,
+    structs::*,
+    Vars,
+};
+
+/// The kind of round a set is in, as set durations differ between them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundType {
+
+    /// A round on the winners side, or of a round robin or Swiss pool.
+    Winners,
+
+    /// A round on the losers side.
+    Losers,
+
+    /// The winners, losers or single elimination final.
+    Finals,
+
+    /// The grand finals and its reset.
+    GrandFinals,
+
+}
+
+impl RoundType {
+
+    /// Returns the round type of a set, from its round and round text.
+    pub fn of(set: &GGSet) -> RoundType {
+        let text = set.full_round_text().to_lowercase();
+        if text.contains("grand final") {
+            return RoundType::GrandFinals;
+        }
+        if text.ends_with("final") && !text.contains("semi") && !text.contains("quarter") {
+            return RoundType::Finals;
+        }
+        if set.round() < 0 {
+            return RoundType::Losers;
+        }
+        return RoundType::Winners;
+    }
+
+}
+
+/// Options for schedule estimates.
+#[derive(Clone)]
+pub struct ScheduleOptions {
+
+    /// The duration of a set when no sets have been completed yet.
+    pub default_set_duration:   Duration,
+
+    /// How far past its planned finish a phase group can be predicted to finish before it's behind schedule.
+    pub tolerance:              Duration,
+
+}
+
+impl Default for ScheduleOptions {
+    fn default() -> Self {
+        return ScheduleOptions { default_set_duration: Duration::from_secs(10 * 60), tolerance: Duration::from_secs(15 * 60) };
+    }
+}
+
+/// The predicted finish of a round with sets left to play.
+#[derive(Clone)]
+pub struct RoundEstimate {
+
+    pub round:              i64,
+    pub round_text:         String,
+    pub remaining_sets:     usize,
+    pub finish:             DateTime<Utc>,
+
+}
+
+/// The predicted finish of a phase group.
+#[derive(Clone)]
+pub struct PhaseGroupEstimate {
+
+    pub phase_group:        GGPhaseGroup,
+
+    /// When the phase group is scheduled to start, from the phase group or its wave.
+    pub scheduled_start:    DateTime<Utc>,
+
+    /// When the phase group would finish if it started on time and every set took the median duration.
+    pub planned_finish:     DateTime<Utc>,
+    pub predicted_finish:   DateTime<Utc>,
+
+    /// The rounds with sets left to play, in the order they are predicted to finish.
+    pub rounds:             Vec<RoundEstimate>,
+
+    /// If the phase group is predicted to finish later than planned by more than the tolerance.
+    ///
+    /// Always false once the phase group is completed.
+    pub behind_schedule:    bool,
+
+}
+
+impl PhaseGroupEstimate {
+
+    /// Returns how much later than planned the phase group is predicted to finish, negative if it's ahead of schedule.
+    pub fn delay(&self) -> chrono::Duration {
+        return self.predicted_finish - self.planned_finish;
+    }
+
+}
+
+/// Predicts when phase groups will finish, from the durations of completed sets.
+#[derive(Clone, Default)]
+pub struct ScheduleEstimator {
+
+    options:    ScheduleOptions,
+
+    /// The durations of completed sets in seconds, by round type.
+    durations:  HashMap<RoundType, Vec<i64>>,
+
+}
+
+impl ScheduleEstimator {
+
+    /// Creates an estimator that hasn't learned any set durations.
+    pub fn new(options: ScheduleOptions) -> ScheduleEstimator {
+        return ScheduleEstimator { options: options, durations: HashMap::new() };
+    }
+
+    /// Learns the durations of completed sets.
+    ///
+    /// Sets need their round, round text, state and the times they were started and completed. Sets without both times are skipped.
+    pub fn learn(&mut self, sets: &[GGSet]) {
+        for set in sets {
+            if set.state() != GGActivityState::Completed as i64 || set.started_at.is_none() || set.completed_at.is_none() {
+                continue;
+            }
+            let duration = set.completed_at.unwrap() - set.started_at.unwrap();
+            if duration > 0 {
+                self.durations.entry(RoundType::of(set)).or_default().push(duration);
+            }
+        }
+    }
+
+    /// Returns the median duration of the sets of a round type.
+    ///
+    /// Falls back to the median of every completed set when none of the round type have been learned, and then to the default set duration.
+    pub fn median_duration(&self, round_type: RoundType) -> Duration {
+        let durations = match self.durations.get(&round_type) {
+            Some(durations) => durations.clone(),
+            None => self.durations.values().flatten().cloned().collect(),
+        };
+        return match median(durations) {
+            Some(seconds) => Duration::from_secs(seconds as u64),
+            None => self.options.default_set_duration,
+        };
+    }
+
+    /// Predicts when each phase group will finish.
+    ///
+    /// Each phase group needs its sets (see get_phase_group_sets()), start time, wave and state. The enabled stations are shared evenly between the unfinished phase groups of each wave,
+    /// or of each start time for phase groups without a wave, with at least one station for each.
+    /// Only the given phase groups share the stations, so stations also used by other events make the estimates optimistic.
+    pub fn estimate(&self, phase_groups: &[GGPhaseGroup], stations: &[GGStations], now: DateTime<Utc>) -> Vec<PhaseGroupEstimate> {
+        let enabled = stations.iter().filter(|station| station.enabled.unwrap_or(true)).count();
+        let slot = |phase_group: &GGPhaseGroup| match &phase_group.wave {
+            Some(wave) if wave.id.is_some() => format!("wave {}", wave.id()),
+            _ => format!("start {}", scheduled_start(phase_group).timestamp()),
+        };
+        let mut sharing: HashMap<String, usize> = HashMap::new();
+        for phase_group in phase_groups {
+            if phase_group.state() != GGActivityState::Completed as i64 {
+                *sharing.entry(slot(phase_group)).or_insert(0) += 1;
+            }
+        }
+
+        let mut result: Vec<PhaseGroupEstimate> = Vec::new();
+        for phase_group in phase_groups {
+            let stations = (enabled / sharing.get(&slot(phase_group)).cloned().unwrap_or(1)).max(1);
+            let sets = phase_group.sets();
+            let start = match scheduled_start(phase_group) {
+                start if start.timestamp() > 0 => start,
+                // Without a schedule, the phase group is planned from its first set.
+                _ => sets.iter().filter(|set| set.started_at.is_some()).map(|set| set.started_at()).min().unwrap_or(now),
+            };
+
+            let planned = self.simulate(&sets, start, stations, None);
+            let predicted = self.simulate(&sets, start, stations, Some(now));
+            let finish = |finishes: &HashMap<String, i64>| Utc.timestamp_opt(finishes.values().cloned().max().unwrap_or(start.timestamp()), 0).unwrap();
+            let (planned_finish, predicted_finish) = (finish(&planned), finish(&predicted));
+
+            let mut rounds: Vec<RoundEstimate> = Vec::new();
+            for set in sets.iter().filter(|set| set.state() != GGActivityState::Completed as i64) {
+                let set_finish = match predicted.get(&set.id().to_string()) {
+                    Some(finish) => Utc.timestamp_opt(*finish, 0).unwrap(),
+                    None => continue,
+                };
+                match rounds.iter_mut().find(|round| round.round == set.round()) {
+                    Some(round) => {
+                        round.remaining_sets += 1;
+                        round.finish = round.finish.max(set_finish);
+                    },
+                    None => rounds.push(RoundEstimate { round: set.round(), round_text: set.full_round_text(), remaining_sets: 1, finish: set_finish }),
+                }
+            }
+            rounds.sort_by_key(|round| (round.finish, round.round.abs()));
+
+            let tolerance = chrono::Duration::from_std(self.options.tolerance).unwrap_or_default();
+            let completed = phase_group.state() == GGActivityState::Completed as i64;
+            result.push(PhaseGroupEstimate {
+                phase_group:        phase_group.clone(),
+                scheduled_start:    start,
+                planned_finish:     planned_finish,
+                predicted_finish:   predicted_finish,
+                rounds:             rounds,
+                behind_schedule:    !completed && predicted_finish - planned_finish > tolerance,
+            });
+        }
+        return result;
+    }
+
+    /// Plays out the sets of a phase group, returning when each set finishes as a timestamp.
+    ///
+    /// Without the current time every set is planned from the start, otherwise completed and started sets keep their times and the rest start from now.
+    fn simulate(&self, sets: &[GGSet], start: DateTime<Utc>, stations: usize, now: Option<DateTime<Utc>>) -> HashMap<String, i64> {
+        let bracket = Bracket::new(sets.to_vec());
+        let origin = match now {
+            Some(now) => start.max(now).timestamp(),
+            None => start.timestamp(),
+        };
+        let duration = |set: &GGSet| self.median_duration(RoundType::of(set)).as_secs() as i64;
+        let entrants = |set: &GGSet| -> Vec<String> {
+            set.slots().iter().filter(|slot| slot.entrant.is_some()).map(|slot| slot.entrant().id().to_string()).collect()
+        };
+
+        let mut finishes: HashMap<String, i64> = HashMap::new();
+        let mut station_free: Vec<i64> = vec![origin; stations.max(1)];
+        let mut entrant_free: HashMap<String, i64> = HashMap::new();
+        let mut pending: Vec<&GGSet> = Vec::new();
+
+        // The grand finals reset is only played when the losers side wins the grand finals, which start.gg shows by filling its slots.
+        let reset_id = bracket.grand_finals_reset().map(|set| set.id().to_string());
+        for set in sets {
+            if Some(set.id().to_string()) == reset_id && set.state() != GGActivityState::Completed as i64 && entrants(set).len() < 2 {
+                continue;
+            }
+            let state = GGActivityState::try_from(set.state()).ok();
+            match (now, state) {
+                (Some(_), Some(GGActivityState::Completed)) => {
+                    let finish = if set.completed_at.is_some() { set.completed_at() } else { Utc.timestamp_opt(origin, 0).unwrap() };
+                    finishes.insert(set.id().to_string(), finish.timestamp());
+                },
+                (Some(_), Some(GGActivityState::Active)) => {
+                    let started = if set.started_at.is_some() { set.started_at().timestamp() } else { origin };
+                    let finish = (started + duration(set)).max(origin);
+                    finishes.insert(set.id().to_string(), finish);
+                    let station = earliest(&station_free);
+                    station_free[station] = station_free[station].max(finish);
+                    for entrant in entrants(set) {
+                        entrant_free.insert(entrant, finish);
+                    }
+                },
+                _ => pending.push(set),
+            }
+        }
+
+        while !pending.is_empty() {
+            // The next set to start is the one that can start earliest, once every set before it has a finish.
+            let mut next: Option<(usize, i64)> = None;
+            for (index, set) in pending.iter().enumerate() {
+                let prerequisites = bracket.prerequisites(&set.id());
+                if prerequisites.iter().any(|edge| !finishes.contains_key(&edge.from.to_string()) && pending.iter().any(|other| other.id() == edge.from)) {
+                    continue;
+                }
+                let mut ready = origin;
+                for edge in prerequisites {
+                    ready = ready.max(finishes.get(&edge.from.to_string()).cloned().unwrap_or(origin));
+                }
+                for entrant in entrants(set) {
+                    ready = ready.max(entrant_free.get(&entrant).cloned().unwrap_or(origin));
+                }
+                if next.is_none_or(|(_, best)| ready < best) {
+                    next = Some((index, ready));
+                }
+            }
+            let (index, ready) = match next {
+                Some(next) => next,
+                // A malformed bracket could loop, which leaves its sets without a finish.
+                None => break,
+            };
+            let set = pending.remove(index);
+            let station = earliest(&station_free);
+            let finish = ready.max(station_free[station]) + duration(set);
+            station_free[station] = finish;
+            for entrant in entrants(set) {
+                entrant_free.insert(entrant, finish);
+            }
+            finishes.insert(set.id().to_string(), finish);
+        }
+        return finishes;
+    }
+
+}
+
+/// Returns when a phase group is scheduled to start, from the phase group or otherwise its wave.
+///
+/// Returns the zero timestamp if neither has a start time.
+fn scheduled_start(phase_group: &GGPhaseGroup) -> DateTime<Utc> {
+    if phase_group.start_at.is_some() {
+        return phase_group.start_at();
+    }
+    return phase_group.wave().start_at();
+}
+
+/// Returns the index of the station that is free first.
+fn earliest(station_free: &[i64]) -> usize {
+    return station_free.iter().enumerate().min_by_key(|(_, free)| **free).map(|(index, _)| index).unwrap_or(0);
+}
+
+/// Returns the median of durations.
+fn median(mut durations: Vec<i64>) -> Option<i64> {
+    if durations.is_empty() {
+        return None;
+    }
+    durations.sort();
+    let middle = durations.len() / 2;
+    if durations.len().is_multiple_of(2) {
+        return Some((durations[middle - 1] + durations[middle]) / 2);
+    }
+    return Some(durations[middle]);
+}
+
+/// Fetches an event's phase groups, their sets and the tournament's stations, and predicts when each phase group will finish.
+///
+/// Set durations are learned from every completed set in the event. Every station of the tournament is shared between this event's phase groups only,
+/// so for tournaments running several events at once the estimates are optimistic. Returns the error message if a query failed.
+pub async fn estimate_event_schedule(
+    event_id: GGID,
+    options: ScheduleOptions,
+    token: &str,
+) -> Result<Vec<PhaseGroupEstimate>, String> {
+
+    let query = r#"
+    query EventSchedule($id: ID!, $per_page: Int!) {
+        event(id: $id) {
+            id
+            phaseGroups {
+                id
+                displayIdentifier
+                startAt
+                state
+                wave {
+                    id
+                    identifier
+                    startAt
+                }
+            }
+            tournament {
+                id
+                stations(page: 1, perPage: $per_page) {
+                    nodes {
+                        id
+                        number
+                        enabled
+                    }
+                }
+            }
+        }
+    }
+    "#;
+
+    let vars = Vars { id: event_id, slug: "".to_string(), page: 1, per_page: 500 };
+
+    let event = match execute_query(token, query, vars).await {
+        GGResponse::Data(data) => data.event(),
+        GGResponse::Error(e) => return Err(e),
+    };
+    let mut phase_groups = event.phase_groups();
+    let mut estimator = ScheduleEstimator::new(options);
+    for phase_group in phase_groups.iter_mut() {
+        match get_phase_group_sets(phase_group.id(), SetFilters::default(), token).await {
+            GGResponse::Data(data) => phase_group.sets = data.phase_group().sets,
+            GGResponse::Error(e) => return Err(e),
+        }
+        estimator.learn(&phase_group.sets());
+    }
+    return Ok(estimator.estimate(&phase_groups, &event.tournament().stations(), Utc::now()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(id: i64, round: i64, text: &str, prereqs: Vec<i64>, times: Option<(i64, i64)>) -> GGSet {
+        let slots = prereqs.iter().map(|prereq| GGSetSlot {
+            prereq_id: Some(prereq.to_string()),
+            prereq_type: Some("set".to_string()),
+            prereq_placement: Some(1),
+            ..Default::default()
+        }).collect();
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            round: Some(round),
+            full_round_text: Some(text.to_string()),
+            state: Some(if times.is_some() { GGActivityState::Completed } else { GGActivityState::Created } as i64),
+            started_at: times.map(|(started, _)| started),
+            completed_at: times.map(|(_, completed)| completed),
+            slots: Some(slots),
+            ..Default::default()
+        };
+    }
+
+    fn phase_group(sets: Vec<GGSet>) -> GGPhaseGroup {
+        return GGPhaseGroup {
+            id: Some(GGID::Int(1)),
+            wave: Some(Box::new(GGWave { id: Some(1), start_at: Some(1000), ..Default::default() })),
+            sets: Some(GGSetConnection { nodes: sets, page_info: None }),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn durations_are_learned_per_round_type() {
+        let mut estimator = ScheduleEstimator::new(Default::default());
+        assert_eq!(estimator.median_duration(RoundType::Winners).as_secs(), 600);
+
+        estimator.learn(&[
+            set(1, 1, "Winners Round 1", vec![], Some((0, 300))),
+            set(2, 1, "Winners Round 1", vec![], Some((0, 900))),
+            set(3, 1, "Winners Round 1", vec![], Some((0, 600))),
+            set(4, -1, "Losers Round 1", vec![], Some((0, 1200))),
+            set(5, 2, "Winners Final", vec![], None),
+        ]);
+        assert_eq!(estimator.median_duration(RoundType::Winners).as_secs(), 600);
+        assert_eq!(estimator.median_duration(RoundType::Losers).as_secs(), 1200);
+        assert_eq!(estimator.median_duration(RoundType::GrandFinals).as_secs(), 750);
+        assert!(RoundType::of(&set(5, 2, "Winners Final", vec![], None)) == RoundType::Finals);
+        assert!(RoundType::of(&set(5, 2, "Winners Semi-Final", vec![], None)) == RoundType::Winners);
+    }
+
+    #[test]
+    fn late_phase_groups_are_behind_schedule() {
+        let sets = vec![
+            set(1, 1, "Winners Round 1", vec![], Some((1000, 1600))),
+            set(2, 1, "Winners Round 1", vec![], Some((1600, 2800))),
+            set(3, 2, "Final", vec![1, 2], None),
+        ];
+        let mut estimator = ScheduleEstimator::new(Default::default());
+        estimator.learn(&sets);
+        let stations = vec![GGStations { id: Some(1), ..Default::default() }];
+
+        // The wave starts at 1000 and both sets take 900 seconds when planned, so the final is planned to finish at 3700.
+        let estimate = &estimator.estimate(&[phase_group(sets.clone())], &stations, Utc.timestamp_opt(2800, 0).unwrap())[0];
+        assert_eq!(estimate.scheduled_start.timestamp(), 1000);
+        assert_eq!(estimate.planned_finish.timestamp(), 3700);
+        assert_eq!(estimate.predicted_finish.timestamp(), 3700);
+        assert_eq!(estimate.rounds.len(), 1);
+        assert_eq!((estimate.rounds[0].round_text.as_str(), estimate.rounds[0].remaining_sets), ("Final", 1));
+        assert!(!estimate.behind_schedule);
+
+        let estimate = &estimator.estimate(&[phase_group(sets.clone())], &stations, Utc.timestamp_opt(4000, 0).unwrap())[0];
+        assert_eq!(estimate.predicted_finish.timestamp(), 4900);
+        assert_eq!(estimate.delay().num_seconds(), 1200);
+        assert!(estimate.behind_schedule);
+
+        // A completed phase group is no longer behind schedule, however late it finished.
+        let mut completed = phase_group(sets);
+        completed.state = Some(GGActivityState::Completed as i64);
+        assert!(!estimator.estimate(&[completed], &stations, Utc.timestamp_opt(4000, 0).unwrap())[0].behind_schedule);
+    }
+}
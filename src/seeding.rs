@@ -0,0 +1,277 @@
+//! Seed performance and upset analytics, used to measure how entrants did compared to their seeds.
+//!
+//! Placements are grouped into the buckets of a double elimination bracket (1, 2, 3, 4, 5, 7, 9, 13, 17, 25, ...), where every entrant in a bucket is eliminated in the same round.
+//! Seed Performance Rating is the number of buckets an entrant placed above or below the bucket of their seed, and the upset factor of a set is the number of buckets between the seeds of its winner and loser.
+
+use std::collections::HashMap;
+
+use crate::{
+    enums::*,
+    execute_query,
+    get_event_sets,
+    get_event_standings,
+    get_phase_seeds,
+    inputs::*,
+    structs::*,
+    Vars,
+};
+
+/// Returns the index of the double elimination bucket a placement or seed falls in, with 1st being bucket 0.
+///
+/// Returns 0 for placements below 1.
+pub fn placement_bucket(placement: i64) -> i64 {
+    let mut bucket = 0;
+    let mut start = 1;
+    while start <= placement {
+        let next = next_bucket_start(start);
+        if next > placement {
+            break;
+        }
+        bucket += 1;
+        start = next;
+    }
+    return bucket;
+}
+
+/// Returns the placements the double elimination buckets start at, up to and including the bucket of a placement.
+pub fn placement_buckets(up_to: i64) -> Vec<i64> {
+    let mut result: Vec<i64> = vec![1];
+    while next_bucket_start(*result.last().unwrap()) <= up_to.max(1) {
+        result.push(next_bucket_start(*result.last().unwrap()));
+    }
+    return result;
+}
+
+/// Returns the placement the bucket after the one starting at a placement starts at.
+fn next_bucket_start(start: i64) -> i64 {
+    if start < 5 {
+        return start + 1;
+    }
+    // Buckets alternate between a quarter and a half of the placements above them, 5 -> 7 -> 9 -> 13 -> 17.
+    let power = 1 << (63 - (start - 1).leading_zeros());
+    if start - 1 == power {
+        return start + power / 2;
+    }
+    return power * 2 + 1;
+}
+
+/// Returns the upset factor of a set won by the winner seed against the loser seed.
+///
+/// Positive when the winner was seeded lower than the loser, zero or negative otherwise.
+pub fn upset_factor(winner_seed: i64, loser_seed: i64) -> i64 {
+    return placement_bucket(winner_seed) - placement_bucket(loser_seed);
+}
+
+/// How an entrant placed compared to their seed.
+#[derive(Clone)]
+pub struct SeedPerformance {
+
+    pub entrant:        GGEntrant,
+    pub seed:           i64,
+    pub placement:      i64,
+
+    /// The number of buckets the entrant placed above their seed, negative if they placed below it.
+    pub spr:            i64,
+
+}
+
+/// A completed set with the seeds of its entrants.
+#[derive(Clone)]
+pub struct SetUpset {
+
+    pub set:            GGSet,
+    pub winner:         GGEntrant,
+    pub loser:          GGEntrant,
+    pub winner_seed:    i64,
+    pub loser_seed:     i64,
+    pub upset_factor:   i64,
+
+}
+
+/// The seeds, standings and sets of an event, with the seed performance and upsets computed from them.
+#[derive(Clone, Default)]
+pub struct SeedReport {
+
+    seeds:      HashMap<String, i64>,
+    standings:  Vec<GGStanding>,
+    sets:       Vec<GGSet>,
+
+}
+
+impl SeedReport {
+
+    /// Creates a report from an event's seeds, standings and sets.
+    ///
+    /// Seeds without an entrant are skipped. Entrants missing from the seeds use their initial seed when it was queried.
+    pub fn new(seeds: &[GGSeed], standings: Vec<GGStanding>, sets: Vec<GGSet>) -> SeedReport {
+        let mut seed_nums: HashMap<String, i64> = HashMap::new();
+        for seed in seeds {
+            if seed.entrant.is_some() && seed.seed_num() > 0 {
+                seed_nums.insert(seed.entrant().id().to_string(), seed.seed_num());
+            }
+        }
+        return SeedReport { seeds: seed_nums, standings: standings, sets: sets };
+    }
+
+    /// Fetches the seeds of an event's first phase, its standings and its sets, and creates a report.
+    ///
+    /// Returns the error message if a query failed.
+    pub async fn load(event_id: GGID, token: &str) -> Result<SeedReport, String> {
+
+        let query = r#"
+        query EventPhases($id: ID!) {
+            event(id: $id) {
+                id
+                phases {
+                    id
+                    phaseOrder
+                }
+            }
+        }
+        "#;
+
+        let vars = Vars { id: event_id.clone(), slug: "".to_string(), page: 1, per_page: 100 };
+
+        let phase = match execute_query(token, query, vars).await {
+            GGResponse::Data(data) => data.event().phases().into_iter().min_by_key(|phase| phase.phase_order()),
+            GGResponse::Error(e) => return Err(e),
+        };
+        let seeds = match phase {
+            Some(phase) => match get_phase_seeds(phase.id(), Default::default(), token).await {
+                GGResponse::Data(data) => data.phase().seeds(),
+                GGResponse::Error(e) => return Err(e),
+            },
+            None => Vec::new(),
+        };
+        let standings = match get_event_standings(event_id.clone(), Default::default(), token).await {
+            GGResponse::Data(data) => data.event().standings(),
+            GGResponse::Error(e) => return Err(e),
+        };
+        let filters = SetFilters { state: Some(vec![GGActivityState::Completed as i64]), ..Default::default() };
+        let sets = match get_event_sets(event_id, filters, token).await {
+            GGResponse::Data(data) => data.event().sets(),
+            GGResponse::Error(e) => return Err(e),
+        };
+        return Ok(SeedReport::new(&seeds, standings, sets));
+    }
+
+    /// Returns the seed of an entrant.
+    ///
+    /// Returns None if the entrant wasn't seeded.
+    pub fn seed(&self, entrant: &GGEntrant) -> Option<i64> {
+        if let Some(seed) = self.seeds.get(&entrant.id().to_string()) {
+            return Some(*seed);
+        }
+        if entrant.initial_seed_num() > 0 {
+            return Some(entrant.initial_seed_num());
+        }
+        return None;
+    }
+
+    /// Returns the seed performance of every seeded entrant with a placement, in the order of the standings.
+    pub fn seed_performance(&self) -> Vec<SeedPerformance> {
+        let mut result: Vec<SeedPerformance> = Vec::new();
+        for standing in &self.standings {
+            if standing.entrant.is_none() || standing.placement() < 1 {
+                continue;
+            }
+            let entrant = standing.entrant();
+            if let Some(seed) = self.seed(&entrant) {
+                result.push(SeedPerformance {
+                    entrant:    entrant,
+                    seed:       seed,
+                    placement:  standing.placement(),
+                    spr:        placement_bucket(seed) - placement_bucket(standing.placement()),
+                });
+            }
+        }
+        return result;
+    }
+
+    /// Returns every completed set between two seeded entrants with its upset factor, in the order of the sets.
+    ///
+    /// Sets won by disqualification are left out, as they weren't played.
+    pub fn sets(&self) -> Vec<SetUpset> {
+        let mut result: Vec<SetUpset> = Vec::new();
+        for set in &self.sets {
+            if set.state() != GGActivityState::Completed as i64 || set.winner_id.is_none() || set.display_score() == "DQ" {
+                continue;
+            }
+            let entrants: Vec<GGEntrant> = set.slots().iter().filter(|slot| slot.entrant.is_some()).map(|slot| slot.entrant()).collect();
+            if entrants.len() != 2 {
+                continue;
+            }
+            let winner_index = match entrants.iter().position(|entrant| entrant.id().to_string() == set.winner_id().to_string()) {
+                Some(index) => index,
+                None => continue,
+            };
+            let (winner, loser) = (entrants[winner_index].clone(), entrants[1 - winner_index].clone());
+            if let (Some(winner_seed), Some(loser_seed)) = (self.seed(&winner), self.seed(&loser)) {
+                result.push(SetUpset {
+                    set:            set.clone(),
+                    winner:         winner,
+                    loser:          loser,
+                    winner_seed:    winner_seed,
+                    loser_seed:     loser_seed,
+                    upset_factor:   upset_factor(winner_seed, loser_seed),
+                });
+            }
+        }
+        return result;
+    }
+
+    /// Returns the sets won by the lower seed, ordered by upset factor from highest to lowest, up to a limit.
+    ///
+    /// Sets with the same upset factor are ordered by the loser's seed, so upsets over better seeds come first.
+    pub fn biggest_upsets(&self, limit: usize) -> Vec<SetUpset> {
+        let mut upsets: Vec<SetUpset> = self.sets().into_iter().filter(|upset| upset.upset_factor > 0).collect();
+        upsets.sort_by_key(|upset| (-upset.upset_factor, upset.loser_seed));
+        upsets.truncate(limit);
+        return upsets;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(id: i64) -> Option<Box<GGEntrant>> {
+        return Some(Box::new(GGEntrant { id: Some(GGID::Int(id)), ..Default::default() }));
+    }
+
+    #[test]
+    fn placements_are_bucketed() {
+        assert_eq!(placement_buckets(49), vec![1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49]);
+        let buckets: Vec<i64> = [1, 2, 3, 4, 5, 6, 7, 8, 9, 12, 13, 16, 17, 24, 25, 33, 48, 49, 64, 65].iter().map(|p| placement_bucket(*p)).collect();
+        assert_eq!(buckets, vec![0, 1, 2, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 10, 10, 11, 11, 12]);
+        assert_eq!(upset_factor(9, 2), 5);
+        assert_eq!(upset_factor(2, 9), -5);
+    }
+
+    #[test]
+    fn seed_performance_and_upsets() {
+        let seeds: Vec<GGSeed> = (1..=8).map(|seed| GGSeed { seed_num: Some(seed), entrant: entrant(seed * 10), ..Default::default() }).collect();
+        let standings = vec![
+            GGStanding { placement: Some(1), entrant: entrant(10), ..Default::default() },
+            GGStanding { placement: Some(3), entrant: entrant(70), ..Default::default() },
+            GGStanding { placement: Some(7), entrant: entrant(20), ..Default::default() },
+        ];
+        let set = |winner: i64, loser: i64, score: &str| GGSet {
+            state: Some(GGActivityState::Completed as i64),
+            winner_id: Some(winner),
+            display_score: Some(score.to_string()),
+            slots: Some(vec![GGSetSlot { entrant: entrant(loser), ..Default::default() }, GGSetSlot { entrant: entrant(winner), ..Default::default() }]),
+            ..Default::default()
+        };
+        let sets = vec![set(10, 80, "2 - 0"), set(70, 20, "2 - 1"), set(50, 40, "DQ"), set(60, 30, "2 - 0")];
+        let report = SeedReport::new(&seeds, standings, sets);
+
+        let spr: Vec<i64> = report.seed_performance().iter().map(|performance| performance.spr).collect();
+        assert_eq!(spr, vec![0, 3, -4]);
+
+        assert_eq!(report.sets().len(), 3);
+        let upsets: Vec<(i64, i64, i64)> = report.biggest_upsets(5).iter().map(|upset| (upset.winner_seed, upset.loser_seed, upset.upset_factor)).collect();
+        assert_eq!(upsets, vec![(7, 2, 4), (6, 3, 2)]);
+    }
+}
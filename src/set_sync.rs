@@ -0,0 +1,225 @@
+//! Incremental syncing of sets, used to keep a local copy of a bracket up to date without fetching every set on each refresh.
+
+use chrono::{
+    DateTime,
+    TimeDelta,
+    Utc,
+};
+
+use std::collections::HashMap;
+
+use crate::{
+    enums::*,
+    get_event_sets,
+    get_phase_group_sets,
+    inputs::*,
+    structs::*,
+};
+
+/// How far each sync reaches back before the latest update it has seen.
+const SYNC_OVERLAP: TimeDelta = TimeDelta::seconds(5);
+
+/// What a set sync fetches sets from.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum SyncTarget {
+
+    PhaseGroup(GGID),
+    Event(GGID),
+
+}
+
+/// Local copy of the sets of phase groups and events.
+///
+/// The first sync of a target fetches all of its sets. Each sync after that only fetches the sets updated since the latest update it has seen, using the updatedAfter filter, and merges them into the local copy.
+#[derive(Clone, Default)]
+pub struct SetSync {
+
+    last_synced:    HashMap<SyncTarget, DateTime<Utc>>,
+    sets:           HashMap<GGID, GGSet>,
+
+}
+
+impl SetSync {
+
+    /// Creates an empty sync.
+    pub fn new() -> SetSync {
+        return Default::default();
+    }
+
+    /// Syncs the sets of a phase group.
+    ///
+    /// Returns the sets that were added or changed since the last sync, or the error message if the query failed.
+    pub async fn sync_phase_group(&mut self, id: GGID, token: &str) -> Result<Vec<GGSet>, String> {
+        return self.sync(SyncTarget::PhaseGroup(id), token).await;
+    }
+
+    /// Syncs the sets of an event.
+    ///
+    /// Returns the sets that were added or changed since the last sync, or the error message if the query failed.
+    pub async fn sync_event(&mut self, id: GGID, token: &str) -> Result<Vec<GGSet>, String> {
+        return self.sync(SyncTarget::Event(id), token).await;
+    }
+
+    /// Syncs the sets of a phase group or event.
+    ///
+    /// Returns the sets that were added or changed since the last sync, or the error message if the query failed.
+    /// The sync time is only updated when the query succeeds, so a failed sync is retried on the next call.
+    pub async fn sync(&mut self, target: SyncTarget, token: &str) -> Result<Vec<GGSet>, String> {
+        let sets = self.fetch(&target, token).await?;
+        return Ok(self.apply(target, sets));
+    }
+
+    /// Fetches the sets of a phase group or event updated since the last sync, without changing the local copy.
+    ///
+    /// Useful for fetching several targets before applying any of them, or comparing the updated sets with the local copy first, see apply().
+    /// Returns the error message if the query failed.
+    pub async fn fetch(&self, target: &SyncTarget, token: &str) -> Result<Vec<GGSet>, String> {
+        let filters = SetFilters {
+            updated_after: self.last_synced.get(target).cloned(),
+            show_byes: Some(true),
+            ..Default::default()
+        };
+        match target {
+            SyncTarget::PhaseGroup(id) => match get_phase_group_sets(id.clone(), filters, token).await {
+                GGResponse::Data(data) => return Ok(data.phase_group().sets()),
+                GGResponse::Error(e) => return Err(e),
+            },
+            SyncTarget::Event(id) => match get_event_sets(id.clone(), filters, token).await {
+                GGResponse::Data(data) => return Ok(data.event().sets()),
+                GGResponse::Error(e) => return Err(e),
+            },
+        }
+    }
+
+    /// Applies the sets fetched for a phase group or event, advancing its sync time and merging them into the local copy.
+    ///
+    /// Returns the sets that were added or changed.
+    pub fn apply(&mut self, target: SyncTarget, sets: Vec<GGSet>) -> Vec<GGSet> {
+        if let Some(watermark) = watermark(&sets) {
+            self.last_synced.insert(target, watermark);
+        }
+        return self.merge(sets);
+    }
+
+    /// Merges sets into the local copy.
+    ///
+    /// Returns the sets that were added or changed.
+    pub fn merge(&mut self, sets: Vec<GGSet>) -> Vec<GGSet> {
+        let mut changed: Vec<GGSet> = Vec::new();
+        for set in sets {
+            if set.id.is_none() {
+                continue;
+            }
+            let is_changed = match self.sets.get(&set.id()) {
+                Some(current) => !same_set(current, &set),
+                None => true,
+            };
+            if is_changed {
+                changed.push(set.clone());
+            }
+            self.sets.insert(set.id(), set);
+        }
+        return changed;
+    }
+
+    /// Returns a set from the local copy.
+    ///
+    /// Returns None if the set hasn't been synced.
+    pub fn set(&self, id: &GGID) -> Option<&GGSet> {
+        return self.sets.get(id);
+    }
+
+    /// Returns every set in the local copy.
+    pub fn sets(&self) -> Vec<GGSet> {
+        return self.sets.values().cloned().collect();
+    }
+
+    /// Returns the time the next sync of a phase group or event fetches updates after.
+    ///
+    /// This is the latest update time of its synced sets, less a small overlap. Returns None if it hasn't been synced, or none of its sets had an update time.
+    pub fn last_synced(&self, target: &SyncTarget) -> Option<DateTime<Utc>> {
+        return self.last_synced.get(target).cloned();
+    }
+
+    /// Forgets when a phase group or event was last synced, so the next sync fetches all of its sets.
+    pub fn reset(&mut self, target: &SyncTarget) {
+        self.last_synced.remove(target);
+    }
+
+}
+
+/// Returns the time the next sync of sets should fetch updates after.
+///
+/// The watermark comes from start.gg's update times rather than the local clock, so a clock running ahead can't skip updates.
+/// It is moved back by SYNC_OVERLAP, as a set can be written in the same second as the latest update returned. Sets fetched again are removed by merge().
+/// Returns None if no set has an update time.
+fn watermark(sets: &[GGSet]) -> Option<DateTime<Utc>> {
+    return sets.iter()
+        .filter(|set| set.updated_at.is_some())
+        .map(|set| set.updated_at())
+        .max()
+        .map(|updated_at| updated_at - SYNC_OVERLAP);
+}
+
+/// Returns the id of the entrant in each of a set's slots.
+fn entrant_ids(set: &GGSet) -> Vec<Option<GGID>> {
+    return set.slots().iter().map(|slot| slot.entrant.as_ref().and_then(|entrant| entrant.id.clone())).collect();
+}
+
+/// Returns if two versions of a set have the same values for everything the set helpers query.
+fn same_set(a: &GGSet, b: &GGSet) -> bool {
+    return a.state == b.state
+        && a.winner_id == b.winner_id
+        && a.display_score == b.display_score
+        && a.started_at == b.started_at
+        && a.completed_at == b.completed_at
+        && a.station.as_ref().and_then(|station| station.id) == b.station.as_ref().and_then(|station| station.id)
+        && a.stream.as_ref().and_then(|stream| stream.id) == b.stream.as_ref().and_then(|stream| stream.id)
+        && entrant_ids(a) == entrant_ids(b);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(id: i64, state: GGActivityState, winner_id: Option<i64>) -> GGSet {
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            state: Some(state as i64),
+            winner_id: winner_id,
+            ..Default::default()
+        };
+    }
+
+    fn ids(sets: &[GGSet]) -> Vec<i64> {
+        let mut result: Vec<i64> = sets.iter().map(|set| match set.id() {
+            GGID::Int(id) => id,
+            GGID::String(_) => 0,
+        }).collect();
+        result.sort();
+        return result;
+    }
+
+    #[test]
+    fn merge_reports_new_and_changed_sets() {
+        let mut sync = SetSync::new();
+        let changed = sync.merge(vec![set(1, GGActivityState::Created, None), set(2, GGActivityState::Active, None)]);
+        assert_eq!(ids(&changed), vec![1, 2]);
+
+        // Sets returned again without changes (such as when sync windows overlap) aren't reported.
+        let changed = sync.merge(vec![set(1, GGActivityState::Created, None), set(2, GGActivityState::Completed, Some(10)), set(3, GGActivityState::Created, None)]);
+        assert_eq!(ids(&changed), vec![2, 3]);
+        assert_eq!(sync.set(&GGID::Int(2)).unwrap().winner_id(), 10);
+        assert_eq!(sync.sets().len(), 3);
+    }
+
+    #[test]
+    fn watermark_follows_server_update_times() {
+        assert!(watermark(&[set(1, GGActivityState::Created, None)]).is_none());
+
+        let mut sets = vec![set(1, GGActivityState::Created, None), set(2, GGActivityState::Active, None), set(3, GGActivityState::Active, None)];
+        sets[0].updated_at = Some(1_000);
+        sets[1].updated_at = Some(1_200);
+        assert_eq!(watermark(&sets).unwrap().timestamp(), 1_200 - SYNC_OVERLAP.num_seconds());
+    }
+}
@@ -0,0 +1,323 @@
+//! Station utilisation over time, built from polled snapshots of a tournament's stations and the sets assigned to them.
+//!
+//! A station is busy while a called or in progress set is assigned to it, and idle otherwise. Each snapshot's state is assumed to hold until the next one.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use std::collections::HashMap;
+
+use crate::{
+    enums::*,
+    execute_query,
+    get_event_sets,
+    inputs::*,
+    structs::*,
+    Vars,
+};
+
+/// A stretch of time a station was busy or idle.
+#[derive(Clone)]
+pub struct StationInterval {
+
+    pub start:          DateTime<Utc>,
+    pub end:            DateTime<Utc>,
+
+    /// The set the station was busy with, or None if it was idle.
+    pub set_id:         Option<GGID>,
+
+}
+
+impl StationInterval {
+
+    /// Returns if the station was busy.
+    pub fn is_busy(&self) -> bool {
+        return self.set_id.is_some();
+    }
+
+    /// Returns the length of the interval.
+    pub fn duration(&self) -> chrono::Duration {
+        return self.end - self.start;
+    }
+
+}
+
+/// The utilisation of a station over the tracked snapshots.
+#[derive(Clone)]
+pub struct StationUsage {
+
+    /// The station, as of the latest snapshot it was in.
+    pub station:            GGStations,
+
+    /// The busy and idle intervals, in order.
+    pub intervals:          Vec<StationInterval>,
+    pub busy_time:          chrono::Duration,
+    pub idle_time:          chrono::Duration,
+
+    /// The number of different sets played on the station.
+    pub sets:               usize,
+
+    /// The average idle time between the end of one set and the start of the next, or None if fewer than two sets were played.
+    pub average_turnaround: Option<chrono::Duration>,
+
+    /// The queue depth of the station at each snapshot.
+    pub queue_depth:        Vec<(DateTime<Utc>, i64)>,
+
+}
+
+impl StationUsage {
+
+    /// Returns the share of the tracked time the station was busy.
+    ///
+    /// Returns zero if no time was tracked.
+    pub fn utilisation(&self) -> f64 {
+        let total = (self.busy_time + self.idle_time).num_seconds();
+        if total == 0 {
+            return 0.0;
+        }
+        return self.busy_time.num_seconds() as f64 / total as f64;
+    }
+
+    /// Returns the deepest the station's queue was.
+    pub fn max_queue_depth(&self) -> i64 {
+        return self.queue_depth.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+    }
+
+}
+
+/// A snapshot where enabled stations were idle while called sets waited for a station.
+#[derive(Clone)]
+pub struct IdleWhileWaiting {
+
+    pub at:             DateTime<Utc>,
+    pub idle_stations:  Vec<GGStations>,
+    pub waiting_sets:   Vec<GGSet>,
+
+}
+
+/// The state of a station in a snapshot.
+#[derive(Clone)]
+struct StationState {
+
+    at:             DateTime<Utc>,
+    set_id:         Option<GGID>,
+    queue_depth:    i64,
+
+}
+
+/// Tracks the utilisation of stations from polled snapshots.
+#[derive(Clone, Default)]
+pub struct StationTracker {
+
+    stations:   HashMap<i64, GGStations>,
+    states:     HashMap<i64, Vec<StationState>>,
+    waiting:    Vec<IdleWhileWaiting>,
+    last:       Option<DateTime<Utc>>,
+
+}
+
+impl StationTracker {
+
+    /// Creates a tracker without any snapshots.
+    pub fn new() -> StationTracker {
+        return Default::default();
+    }
+
+    /// Records a snapshot of the stations and the sets that are called, in progress or queued.
+    ///
+    /// Sets need their state and station. Snapshots older than the latest recorded snapshot are ignored.
+    pub fn record(&mut self, at: DateTime<Utc>, stations: &[GGStations], sets: &[GGSet]) {
+        if self.last.is_some_and(|last| at < last) {
+            return;
+        }
+        self.last = Some(at);
+
+        let is_state = |set: &GGSet, states: &[GGActivityState]| states.iter().any(|state| set.state() == *state as i64);
+        let mut busy: HashMap<i64, GGID> = HashMap::new();
+        for set in sets {
+            if set.station.is_some() && is_state(set, &[GGActivityState::Called, GGActivityState::Active]) {
+                busy.insert(set.station().id(), set.id());
+            }
+        }
+
+        let mut idle: Vec<GGStations> = Vec::new();
+        for station in stations {
+            let set_id = busy.get(&station.id()).cloned();
+            if set_id.is_none() && station.enabled.unwrap_or(true) {
+                idle.push(station.clone());
+            }
+            self.stations.insert(station.id(), station.clone());
+            self.states.entry(station.id()).or_default().push(StationState { at: at, set_id: set_id, queue_depth: station.queue_depth() });
+        }
+
+        let waiting: Vec<GGSet> = sets.iter()
+            .filter(|set| set.station.is_none() && is_state(set, &[GGActivityState::Called, GGActivityState::Queued]))
+            .cloned()
+            .collect();
+        if !idle.is_empty() && !waiting.is_empty() {
+            self.waiting.push(IdleWhileWaiting { at: at, idle_stations: idle, waiting_sets: waiting });
+        }
+    }
+
+    /// Fetches the stations of a tournament and the sets of its events that are called, in progress or queued, and records them as a snapshot.
+    ///
+    /// Returns the error message if a query failed.
+    pub async fn poll(&mut self, tournament_id: GGID, token: &str) -> Result<(), String> {
+
+        let query = r#"
+        query TournamentStations($id: ID!, $per_page: Int!) {
+            tournament(id: $id) {
+                id
+                events {
+                    id
+                }
+                stations(page: 1, perPage: $per_page) {
+                    nodes {
+                        id
+                        number
+                        enabled
+                        queueDepth
+                    }
+                }
+            }
+        }
+        "#;
+
+        let at = Utc::now();
+        let vars = Vars { id: tournament_id, slug: "".to_string(), page: 1, per_page: 500 };
+
+        let tournament = match execute_query(token, query, vars).await {
+            GGResponse::Data(data) => data.tournament(),
+            GGResponse::Error(e) => return Err(e),
+        };
+        let filters = SetFilters {
+            state: Some(vec![GGActivityState::Called as i64, GGActivityState::Active as i64, GGActivityState::Queued as i64]),
+            ..Default::default()
+        };
+        let mut sets: Vec<GGSet> = Vec::new();
+        for event in tournament.events() {
+            match get_event_sets(event.id(), filters.clone(), token).await {
+                GGResponse::Data(data) => sets.append(&mut data.event().sets()),
+                GGResponse::Error(e) => return Err(e),
+            }
+        }
+        self.record(at, &tournament.stations(), &sets);
+        return Ok(());
+    }
+
+    /// Returns the utilisation of every station, ordered by station number.
+    pub fn usage(&self) -> Vec<StationUsage> {
+        let mut result: Vec<StationUsage> = self.stations.keys().filter_map(|id| self.station_usage(*id)).collect();
+        result.sort_by_key(|usage| (usage.station.number(), usage.station.id()));
+        return result;
+    }
+
+    /// Returns the utilisation of a station.
+    ///
+    /// Returns None if the station wasn't in any snapshot.
+    pub fn station_usage(&self, station_id: i64) -> Option<StationUsage> {
+        let station = self.stations.get(&station_id)?.clone();
+        let states = self.states.get(&station_id)?;
+
+        // Consecutive snapshots with the same set are merged into one interval.
+        let mut intervals: Vec<StationInterval> = Vec::new();
+        for window in states.windows(2) {
+            let (state, next) = (&window[0], &window[1]);
+            match intervals.last_mut() {
+                Some(last) if last.set_id == state.set_id => last.end = next.at,
+                _ => intervals.push(StationInterval { start: state.at, end: next.at, set_id: state.set_id.clone() }),
+            }
+        }
+
+        let zero = chrono::Duration::zero();
+        let busy_time = intervals.iter().filter(|interval| interval.is_busy()).fold(zero, |total, interval| total + interval.duration());
+        let idle_time = intervals.iter().filter(|interval| !interval.is_busy()).fold(zero, |total, interval| total + interval.duration());
+
+        let mut set_ids: Vec<String> = states.iter().filter_map(|state| state.set_id.as_ref().map(|id| id.to_string())).collect();
+        set_ids.sort();
+        set_ids.dedup();
+
+        // Turnaround is the time from the end of one set to the start of the next, including when one set directly follows another.
+        let mut turnarounds: Vec<chrono::Duration> = Vec::new();
+        let mut previous_end: Option<DateTime<Utc>> = None;
+        for interval in intervals.iter().filter(|interval| interval.is_busy()) {
+            if let Some(end) = previous_end {
+                turnarounds.push(interval.start - end);
+            }
+            previous_end = Some(interval.end);
+        }
+        let average_turnaround = match turnarounds.len() {
+            0 => None,
+            count => Some(turnarounds.iter().fold(zero, |total, turnaround| total + *turnaround) / count as i32),
+        };
+
+        return Some(StationUsage {
+            station:            station,
+            intervals:          intervals,
+            busy_time:          busy_time,
+            idle_time:          idle_time,
+            sets:               set_ids.len(),
+            average_turnaround: average_turnaround,
+            queue_depth:        states.iter().map(|state| (state.at, state.queue_depth)).collect(),
+        });
+    }
+
+    /// Returns the snapshots where enabled stations were idle while called or queued sets without a station waited.
+    pub fn idle_while_waiting(&self) -> &[IdleWhileWaiting] {
+        return &self.waiting;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn station(id: i64, queue_depth: i64) -> GGStations {
+        return GGStations { id: Some(id), number: Some(id), queue_depth: Some(queue_depth), ..Default::default() };
+    }
+
+    fn set(id: i64, state: GGActivityState, station_id: Option<i64>) -> GGSet {
+        return GGSet {
+            id: Some(GGID::Int(id)),
+            state: Some(state as i64),
+            station: station_id.map(|id| Box::new(GGStations { id: Some(id), ..Default::default() })),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn utilisation_is_tracked_across_snapshots() {
+        let at = |minutes: i64| Utc.timestamp_opt(minutes * 60, 0).unwrap();
+        let mut tracker = StationTracker::new();
+        tracker.record(at(0), &[station(1, 2), station(2, 0)], &[set(10, GGActivityState::Called, Some(1))]);
+        tracker.record(at(5), &[station(1, 1), station(2, 0)], &[set(10, GGActivityState::Active, Some(1)), set(11, GGActivityState::Called, None)]);
+        tracker.record(at(10), &[station(1, 1), station(2, 0)], &[set(11, GGActivityState::Called, Some(2))]);
+        tracker.record(at(15), &[station(1, 0), station(2, 0)], &[set(12, GGActivityState::Active, Some(1)), set(11, GGActivityState::Active, Some(2))]);
+        tracker.record(at(20), &[station(1, 0), station(2, 0)], &[]);
+        // Out of order snapshots are ignored.
+        tracker.record(at(1), &[station(1, 5)], &[]);
+
+        let usage = tracker.usage();
+        assert_eq!(usage.len(), 2);
+        let first = &usage[0];
+        assert_eq!((first.busy_time.num_minutes(), first.idle_time.num_minutes()), (15, 5));
+        assert_eq!(first.sets, 2);
+        assert_eq!(first.average_turnaround.unwrap().num_minutes(), 5);
+        assert_eq!(first.max_queue_depth(), 2);
+        assert_eq!(first.utilisation(), 0.75);
+
+        let second = &usage[1];
+        assert_eq!((second.busy_time.num_minutes(), second.idle_time.num_minutes()), (10, 10));
+        assert!(second.average_turnaround.is_none());
+
+        let waiting = tracker.idle_while_waiting();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].at, at(5));
+        assert_eq!(waiting[0].idle_stations[0].id(), 2);
+        assert_eq!(waiting[0].waiting_sets[0].id().to_string(), "11");
+    }
+}
@@ -15,6 +15,8 @@ use crate::{
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GGEntrantConnection {
     pub nodes:      Vec<GGEntrant>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -15,8 +15,10 @@ use crate::{
     page_info::*,
     phase::*,
     phase_group::*,
+    set::*,
     standing::*,
     tournament::*,
+    videogame::*,
 };
 
 /// Equivalent for start.gg EventConnection.
@@ -101,7 +103,7 @@ pub struct GGEvent {
 
     #[serde(rename(serialize = "rulesetId",                 deserialize = "rulesetId"))]
     pub ruleset_id:                 Option<i64>,
-    // pub sets:                       Option<GGSets>,
+    pub sets:                       Option<GGSetConnection>,
     pub slug:                       Option<String>,
     pub standings:                  Option<GGStandingConnection>,
 
@@ -129,7 +131,7 @@ pub struct GGEvent {
 
     #[serde(rename(serialize = "userEntrant",               deserialize = "userEntrant"))]
     pub user_entrant:               Option<Box<GGEntrant>>,
-    // pub videogame:                  Option<GGVideogame>,
+    pub videogame:                  Option<Box<GGVideogame>>,
     // pub waves:                      Option<Vec<GGWave>>,
 
 }
@@ -343,6 +345,19 @@ impl GGEvent {
         return result;
     }
 
+    /// Returns the sets in the event.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn sets(&self) -> Vec<GGSet> {
+        let mut result: Vec<GGSet> = Vec::new();
+        if let Some(sets) = &self.sets {
+            for set in &sets.nodes {
+                result.push(set.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the slug of the event.
     ///
     /// Returns an empty string if not set or wasn't queried.
@@ -466,4 +481,15 @@ impl GGEvent {
         return result;
     }
 
+    /// Returns the videogame of the event.
+    ///
+    /// Returns an empty videogame if not set or wasn't queried.
+    pub fn videogame(&self) -> GGVideogame {
+        let mut result: GGVideogame = Default::default();
+        if let Some(videogame) = &self.videogame {
+            result = *videogame.clone();
+        }
+        return result;
+    }
+
 }
@@ -5,6 +5,7 @@ use serde::{
 };
 
 use crate::{
+    enums::*,
     entrant::*,
     participant::*,
 };
@@ -24,7 +25,7 @@ pub struct GGGameSelection {
     pub order_num:              Option<i64>,
     pub participant:            Option<Box<GGParticipant>>,
 
-    #[serde(rename(serialize = "selectionType",     deserialize = "selectionType"))]
+    #[serde(rename(serialize = "selectionType",     deserialize = "selectionType"), default, deserialize_with = "deserialize_game_selection_type")]
     pub selection_type:         Option<i64>,
 
     #[serde(rename(serialize = "selectionValue",    deserialize = "selectionValue"))]
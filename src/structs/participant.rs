@@ -10,15 +10,19 @@ use serde::{
 };
 
 use crate::{
+    contact_info::*,
     entrant::*,
     event::*,
     page_info::*,
+    player::*,
     user::*,
 };
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGParticipantConnection {
     pub nodes:      Vec<GGParticipant>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -51,7 +55,9 @@ pub struct GGParticipant {
     #[serde(rename(serialize = "checkedInAt", deserialize = "checkedInAt"))]
     pub checked_in_at:          Option<i64>,
     // pub connected_accounts:     JSON,
-    // pub contact_info:           Option<GGContactInfo>,
+
+    #[serde(rename(serialize = "contactInfo", deserialize = "contactInfo"))]
+    pub contact_info:           Option<Box<GGContactInfo>>,
     pub email:                  Option<String>,
     pub entrants:               Option<Vec<GGEntrant>>,
     pub events:                 Option<Vec<GGEvent>>,
@@ -60,7 +66,7 @@ pub struct GGParticipant {
     pub gamer_tag:              Option<String>,
     pub id:                     Option<i64>,
     // pub images:                 Option<Vec<GGImage>>,
-    // pub player:                 Option<GGPlayer>,
+    pub player:                 Option<Box<GGPlayer>>,
     pub prefix:                 Option<String>,
     // pub required_connections:   Option<GGProfileAuthorization>,
     pub user:                   Option<Box<GGUser>>,
@@ -91,6 +97,17 @@ impl GGParticipant {
         return Utc.timestamp_opt(result, 0).unwrap();
     }
 
+    /// Returns the contact info of the participant.
+    ///
+    /// Returns empty contact info if not set or wasn't queried.
+    pub fn contact_info(&self) -> GGContactInfo {
+        let mut result: GGContactInfo = Default::default();
+        if let Some(contact_info) = &self.contact_info {
+            result = *contact_info.clone();
+        }
+        return result;
+    }
+
     /// Returns the email of the participant.
     ///
     /// Returns an empty string if not set or wasn't queried.
@@ -150,6 +167,17 @@ impl GGParticipant {
         return result;
     }
 
+    /// Returns the player of the participant.
+    ///
+    /// Returns an empty player if not set or wasn't queried.
+    pub fn player(&self) -> GGPlayer {
+        let mut result: GGPlayer = Default::default();
+        if let Some(player) = &self.player {
+            result = *player.clone();
+        }
+        return result;
+    }
+
     /// Returns the prefix of the participant.
     ///
     /// Returns an empty string if not set or wasn't queried.
@@ -8,6 +8,7 @@ use crate::{
     enums::*,
     event::*,
     phase_group::*,
+    seed::*,
     set::*,
 };
 
@@ -20,7 +21,7 @@ use crate::{
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGPhase {
     
-    #[serde(rename(serialize = "bracketType",   deserialize = "bracketType"))]
+    #[serde(rename(serialize = "bracketType",   deserialize = "bracketType"), default, deserialize_with = "deserialize_bracket_type")]
     pub bracket_type:               Option<i64>,
     pub event:                      Option<Box<GGEvent>>,
 
@@ -40,7 +41,7 @@ pub struct GGPhase {
 
     #[serde(rename(serialize = "phaseOrder",    deserialize = "phaseOrder"))]
     pub phase_order:                Option<i64>,
-    // pub seeds:                      Option<GGSeeds>,
+    pub seeds:                      Option<GGSeedConnection>,
     pub sets:                       Option<GGSetConnection>,
     pub state:                      Option<i64>,
     // pub waves:                      Option<GGWave>,
@@ -153,6 +154,19 @@ impl GGPhase {
         return result;
     }
 
+    /// Returns the seeds in the phase.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn seeds(&self) -> Vec<GGSeed> {
+        let mut result: Vec<GGSeed> = Vec::new();
+        if let Some(seeds) = &self.seeds {
+            for seed in &seeds.nodes {
+                result.push(seed.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the sets in the phase.
     ///
     /// Returns an empty vector if not set or wasn't queried.
@@ -8,12 +8,16 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use serde_json::Value;
 
 use crate::{
     enums::*,
     page_info::*,
     phase::*,
+    seed::*,
     set::*,
+    standing::*,
+    wave::*,
 };
 
 /// Equivalent for start.gg PhaseGroupConnection.
@@ -47,7 +51,7 @@ impl GGPhaseGroupConnection {
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGPhaseGroup {
     
-    #[serde(rename(serialize = "bracketType",       deserialize = "bracketType"))]
+    #[serde(rename(serialize = "bracketType",       deserialize = "bracketType"), default, deserialize_with = "deserialize_bracket_type")]
     pub bracket_type:               Option<i64>,
 
     #[serde(rename(serialize = "bracketUrl",        deserialize = "bracketUrl"))]
@@ -67,16 +71,17 @@ pub struct GGPhaseGroup {
     // pub progressions_out:           Option<GGProgression>,
     // pub rounds:                     Option<GGRound>,
     // pub seed_map:                   JSON,
-    // pub seeds:                      Option<GGSeeds>,
+    pub seeds:                      Option<GGSeedConnection>,
     pub sets:                       Option<GGSetConnection>,
-    // pub standings:                  Option<GGStandings>,
+    pub standings:                  Option<GGStandingConnection>,
 
     #[serde(rename(serialize = "startAt",                   deserialize = "startAt"))]
     pub start_at:                   Option<i64>,
     pub state:                      Option<i64>,
 
-    // pub tiebreaker_order:           JSON,
-    // pub wave:                       Option<Box<GGWave>>,
+    #[serde(rename(serialize = "tiebreakerOrder",           deserialize = "tiebreakerOrder"))]
+    pub tiebreaker_order:           Option<Value>,
+    pub wave:                       Option<Box<GGWave>>,
 
 }
 
@@ -162,6 +167,19 @@ impl GGPhaseGroup {
         return result;
     }
 
+    /// Returns the seeds in the phase group.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn seeds(&self) -> Vec<GGSeed> {
+        let mut result: Vec<GGSeed> = Vec::new();
+        if let Some(seeds) = &self.seeds {
+            for seed in &seeds.nodes {
+                result.push(seed.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the sets in the phase group.
     ///
     /// Returns an empty vector if not set or wasn't queried.
@@ -175,6 +193,19 @@ impl GGPhaseGroup {
         return result;
     }
 
+    /// Returns the standings in the phase group.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn standings(&self) -> Vec<GGStanding> {
+        let mut result: Vec<GGStanding> = Vec::new();
+        if let Some(standings) = &self.standings {
+            for standing in &standings.nodes {
+                result.push(standing.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the time the phase group starts.
     ///
     /// Returns zero if not set or wasn't queried.
@@ -197,4 +228,26 @@ impl GGPhaseGroup {
         return result;
     }
 
+    /// Returns the tiebreaker order of the phase group, as start.gg's JSON.
+    ///
+    /// Returns null if not set or wasn't queried.
+    pub fn tiebreaker_order(&self) -> Value {
+        let mut result: Value = Value::Null;
+        if let Some(tiebreaker_order) = &self.tiebreaker_order {
+            result = tiebreaker_order.clone();
+        }
+        return result;
+    }
+
+    /// Returns the wave of the phase group.
+    ///
+    /// Returns an empty wave if not set or wasn't queried.
+    pub fn wave(&self) -> GGWave {
+        let mut result: GGWave = Default::default();
+        if let Some(wave) = &self.wave {
+            result = *wave.clone();
+        }
+        return result;
+    }
+
 }
@@ -5,6 +5,9 @@ use serde::{
 };
 
 use crate::{
+    player_rank::*,
+    set::*,
+    standing::*,
     user::*,
 };
 
@@ -21,11 +24,11 @@ pub struct GGPlayer {
     pub gamer_tag:          Option<String>,
     pub id:                 Option<i64>,
     pub prefix:             Option<String>,
-    // pub rankings:           Option<GGPlayerRank>,
+    pub rankings:           Option<Vec<GGPlayerRank>>,
     
-    // #[serde(rename(serialize = "recentStandings",   deserialize = "recentStandings"))]
-    // pub recent_standings:   Option<GGStanding>,
-    // pub sets:               Option<GGSets>,
+    #[serde(rename(serialize = "recentStandings",   deserialize = "recentStandings"))]
+    pub recent_standings:   Option<Vec<GGStanding>>,
+    pub sets:               Option<GGSetConnection>,
     pub user:               Option<Box<GGUser>>,
 
 }
@@ -65,6 +68,45 @@ impl GGPlayer {
         return result;
     }
 
+    /// Returns the rankings of the player.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn rankings(&self) -> Vec<GGPlayerRank> {
+        let mut result: Vec<GGPlayerRank> = Vec::new();
+        if let Some(rankings) = &self.rankings {
+            for ranking in rankings {
+                result.push(ranking.clone());
+            }
+        }
+        return result;
+    }
+
+    /// Returns the recent standings of the player.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn recent_standings(&self) -> Vec<GGStanding> {
+        let mut result: Vec<GGStanding> = Vec::new();
+        if let Some(recent_standings) = &self.recent_standings {
+            for standing in recent_standings {
+                result.push(standing.clone());
+            }
+        }
+        return result;
+    }
+
+    /// Returns the sets the player has played.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn sets(&self) -> Vec<GGSet> {
+        let mut result: Vec<GGSet> = Vec::new();
+        if let Some(sets) = &self.sets {
+            for set in &sets.nodes {
+                result.push(set.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the user of the player.
     ///
     /// Returns an empty user if not set or wasn't queried.
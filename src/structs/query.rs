@@ -12,6 +12,7 @@ use crate::{
     phase_group::*,
     player::*,
     set::*,
+    stations::*,
     tournament::*,
     user::*,
     videogame::*,
@@ -23,6 +24,7 @@ use crate::{
 /// Given each is an option and not a requirement, a method is included for each element with the same name.
 /// These methods will unwrap and return the proper value without any unwrapping or references needed.
 /// Certain methods (see tournaments()) will return a vector of the data type instead of a connection to a vector, done to simplify the API and make the start.gg api easier to work with.
+/// The results of mutations are also included, as they are returned the same way as queries.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GGData {
 
@@ -50,6 +52,46 @@ pub struct GGData {
     pub videogame:      Option<Box<GGVideogame>>,
     pub videogames:     Option<Box<GGVideogameConnection>>,
 
+    // Mutations
+    #[serde(rename(serialize = "assignStation",     deserialize = "assignStation"))]
+    pub assign_station:         Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "assignStream",      deserialize = "assignStream"))]
+    pub assign_stream:          Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "deleteStation",     deserialize = "deleteStation"))]
+    pub delete_station:         Option<bool>,
+
+    #[serde(rename(serialize = "generateRegistrationToken", deserialize = "generateRegistrationToken"))]
+    pub generate_registration_token:    Option<String>,
+
+    #[serde(rename(serialize = "markSetCalled",     deserialize = "markSetCalled"))]
+    pub mark_set_called:        Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "markSetInProgress", deserialize = "markSetInProgress"))]
+    pub mark_set_in_progress:   Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "registerForTournament", deserialize = "registerForTournament"))]
+    pub register_for_tournament:    Option<Box<GGParticipant>>,
+
+    #[serde(rename(serialize = "reportBracketSet",  deserialize = "reportBracketSet"))]
+    pub report_bracket_set:     Option<Vec<GGSet>>,
+
+    #[serde(rename(serialize = "resetSet",          deserialize = "resetSet"))]
+    pub reset_set:              Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "updateBracketSet",  deserialize = "updateBracketSet"))]
+    pub update_bracket_set:     Option<Box<GGSet>>,
+
+    #[serde(rename(serialize = "updatePhaseGroups", deserialize = "updatePhaseGroups"))]
+    pub update_phase_groups:    Option<Vec<GGPhaseGroup>>,
+
+    #[serde(rename(serialize = "updatePhaseSeeding",    deserialize = "updatePhaseSeeding"))]
+    pub update_phase_seeding:   Option<Box<GGPhase>>,
+
+    #[serde(rename(serialize = "upsertStation",     deserialize = "upsertStation"))]
+    pub upsert_station:         Option<Box<GGStations>>,
+
 }
 
 impl GGData {
@@ -201,4 +243,151 @@ impl GGData {
         return result;
     }
 
+    /// Returns the set assigned to a station.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to assign a station.
+    pub fn assign_station(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(assign_station) = &self.assign_station {
+            result = *assign_station.clone();
+        }
+        return result;
+    }
+
+    /// Returns the set assigned to a stream.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to assign a stream.
+    pub fn assign_stream(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(assign_stream) = &self.assign_stream {
+            result = *assign_stream.clone();
+        }
+        return result;
+    }
+
+    /// Returns if a station was deleted.
+    ///
+    /// Returns false if not set or wasn't a mutation to delete a station.
+    pub fn delete_station(&self) -> bool {
+        let mut result: bool = false;
+        if let Some(delete_station) = self.delete_station {
+            result = delete_station;
+        }
+        return result;
+    }
+
+    /// Returns the registration token that was generated.
+    ///
+    /// Returns an empty string if not set or wasn't a mutation to generate a registration token.
+    pub fn generate_registration_token(&self) -> String {
+        let mut result: String = "".to_string();
+        if self.generate_registration_token.is_some() {
+            result = self.generate_registration_token.clone().unwrap().clone();
+        }
+        return result;
+    }
+
+    /// Returns the set marked as called.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to mark a set as called.
+    pub fn mark_set_called(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(mark_set_called) = &self.mark_set_called {
+            result = *mark_set_called.clone();
+        }
+        return result;
+    }
+
+    /// Returns the set marked as in progress.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to mark a set as in progress.
+    pub fn mark_set_in_progress(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(mark_set_in_progress) = &self.mark_set_in_progress {
+            result = *mark_set_in_progress.clone();
+        }
+        return result;
+    }
+
+    /// Returns the participant that was registered.
+    ///
+    /// Returns an empty participant if not set or wasn't a mutation to register for a tournament.
+    pub fn register_for_tournament(&self) -> GGParticipant {
+        let mut result: GGParticipant = Default::default();
+        if let Some(register_for_tournament) = &self.register_for_tournament {
+            result = *register_for_tournament.clone();
+        }
+        return result;
+    }
+
+    /// Returns the sets from reporting a set.
+    ///
+    /// Returns an empty vector if not set or wasn't a mutation to report a set.
+    pub fn report_bracket_set(&self) -> Vec<GGSet> {
+        let mut result: Vec<GGSet> = Vec::new();
+        if let Some(report_bracket_set) = &self.report_bracket_set {
+            for set in report_bracket_set {
+                result.push(set.clone());
+            }
+        }
+        return result;
+    }
+
+    /// Returns the set that was reset.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to reset a set.
+    pub fn reset_set(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(reset_set) = &self.reset_set {
+            result = *reset_set.clone();
+        }
+        return result;
+    }
+
+    /// Returns the set that was updated.
+    ///
+    /// Returns an empty set if not set or wasn't a mutation to update a set.
+    pub fn update_bracket_set(&self) -> GGSet {
+        let mut result: GGSet = Default::default();
+        if let Some(update_bracket_set) = &self.update_bracket_set {
+            result = *update_bracket_set.clone();
+        }
+        return result;
+    }
+
+    /// Returns the phase groups that were updated.
+    ///
+    /// Returns an empty vector if not set or wasn't a mutation to update phase groups.
+    pub fn update_phase_groups(&self) -> Vec<GGPhaseGroup> {
+        let mut result: Vec<GGPhaseGroup> = Vec::new();
+        if let Some(update_phase_groups) = &self.update_phase_groups {
+            for phase_group in update_phase_groups {
+                result.push(phase_group.clone());
+            }
+        }
+        return result;
+    }
+
+    /// Returns the phase that had its seeding updated.
+    ///
+    /// Returns an empty phase if not set or wasn't a mutation to update seeding.
+    pub fn update_phase_seeding(&self) -> GGPhase {
+        let mut result: GGPhase = Default::default();
+        if let Some(update_phase_seeding) = &self.update_phase_seeding {
+            result = *update_phase_seeding.clone();
+        }
+        return result;
+    }
+
+    /// Returns the station that was created or updated.
+    ///
+    /// Returns an empty station if not set or wasn't a mutation to create or update a station.
+    pub fn upsert_station(&self) -> GGStations {
+        let mut result: GGStations = Default::default();
+        if let Some(upsert_station) = &self.upsert_station {
+            result = *upsert_station.clone();
+        }
+        return result;
+    }
+
 }
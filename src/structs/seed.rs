@@ -4,6 +4,8 @@ use serde::{
     Serialize,
 };
 
+use std::collections::HashSet;
+
 use crate::{
     entrant::*,
     page_info::*,
@@ -14,9 +16,11 @@ use crate::{
 };
 
 /// Equivalent for start.gg SeedConnection.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGSeedConnection {
     pub nodes:      Vec<GGSeed>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -33,6 +37,15 @@ impl GGSeedConnection {
         return result;
     }
 
+    /// Sorts the seeds by seed number.
+    ///
+    /// Duplicate seeds (which can appear when pages shift between requests) are removed.
+    pub fn sort_by_seed_num(&mut self) {
+        let mut ids: HashSet<i64> = HashSet::new();
+        self.nodes.retain(|seed| seed.id.is_none() || ids.insert(seed.id()));
+        self.nodes.sort_by_key(|seed| seed.seed_num());
+    }
+
 }
 
 /// Equivalent for start.gg Seed.
@@ -25,6 +25,8 @@ use crate::{
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGSetConnection {
     pub nodes:      Vec<GGSet>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -95,6 +97,9 @@ pub struct GGSet {
     #[serde(rename(serialize = "totalGames",        deserialize = "totalGames"))]
     pub total_games:        Option<i64>,
 
+    #[serde(rename(serialize = "updatedAt",         deserialize = "updatedAt"))]
+    pub updated_at:         Option<i64>,
+
     #[serde(rename(serialize = "vodUrl",            deserialize = "vodUrl"))]
     pub vod_url:            Option<String>,
 
@@ -359,6 +364,17 @@ impl GGSet {
         return result;
     }
 
+    /// Returns the time the set was last updated.
+    ///
+    /// Returns zero if not set or wasn't queried.
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        let mut result: i64 = 0;
+        if let Some(updated_at) = self.updated_at {
+            result = updated_at;
+        }
+        return Utc.timestamp_opt(result, 0).unwrap();
+    }
+
     /// Returns the VOD url of the set.
     ///
     /// Returns an empty string if not set or wasn't queried.
@@ -4,6 +4,8 @@ use serde::{
     Serialize,
 };
 
+use std::collections::HashSet;
+
 use crate::{
     entrant::*,
     event::*,
@@ -29,6 +31,8 @@ pub enum GGStandingContainer {
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGStandingConnection {
     pub nodes:      Vec<GGStanding>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -45,6 +49,16 @@ impl GGStandingConnection {
         return result;
     }
 
+    /// Sorts the standings by placement.
+    ///
+    /// Duplicate standings (which can appear when pages shift between requests) are removed.
+    /// Tied standings share a placement and keep the order they were returned in.
+    pub fn sort_by_placement(&mut self) {
+        let mut ids: HashSet<i64> = HashSet::new();
+        self.nodes.retain(|standing| standing.id.is_none() || ids.insert(standing.id()));
+        self.nodes.sort_by_key(|standing| standing.placement());
+    }
+
 }
 
 /// Equivalent for start.gg Standing.
@@ -17,6 +17,8 @@ use crate::{
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGStationsConnection {
     pub nodes:      Vec<GGStations>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -15,6 +15,7 @@ use crate::{
     image::*,
     page_info::*,
     participant::*,
+    stations::*,
     user::*,
 };
 
@@ -22,6 +23,8 @@ use crate::{
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGTournamentConnection {
     pub nodes:      Vec<GGTournament>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -116,7 +119,7 @@ pub struct GGTournament {
     #[serde(rename(serialize = "startAt",                   deserialize = "startAt"))]
     pub start_at:                       Option<i64>,
     pub state:                          Option<i64>,
-    // pub stations
+    pub stations:                       Option<GGStationsConnection>,
     // pub streamQueue
     // pub streams
     
@@ -506,6 +509,19 @@ impl GGTournament {
         return result;
     }
 
+    /// Returns the stations of the tournament.
+    ///
+    /// Returns an empty vector if not set or wasn't queried.
+    pub fn stations(&self) -> Vec<GGStations> {
+        let mut result: Vec<GGStations> = Vec::new();
+        if let Some(stations) = &self.stations {
+            for station in &stations.nodes {
+                result.push(station.clone());
+            }
+        }
+        return result;
+    }
+
     /// Returns the team creation end date of the tournament.
     ///
     /// Returns zero if not set or wasn't queried.
@@ -14,6 +14,8 @@ use crate::{
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct GGVideogameConnection {
     pub nodes:      Vec<GGVideogame>,
+
+    #[serde(rename(serialize = "pageInfo",  deserialize = "pageInfo"))]
     pub page_info:  Option<Box<GGPageInfo>>,
 }
 
@@ -0,0 +1,792 @@
+//! A local mock of start.gg's GraphQL API, used to run tests offline.
+//!
+//! The mock server is seeded with [`MockData`] built from the existing structures and answers queries from it.
+//! Only the queries are resolved, mutations return an error.
+//!
+//! ```ignore
+//! let server = ggapi::testing::MockServer::start(data);
+//! let response = ggapi::get_tournament_info("evo-2023", server.token()).await;
+//! ```
+//!
+//! Every mock server shares a single local listener, and requests are routed to a server by the token they were sent with.
+//! Starting a server points the crate's queries at the listener, so the helper functions work without any changes.
+//! Queries go back to start.gg's API once every running server has been dropped.
+//!
+//! Each requested field is taken from the serialized structures, and connections are paginated by the `page` and `perPage` arguments of the field.
+//! Other arguments, such as filters and sorting, are ignored with the exception of the name filter when searching videogames.
+
+use serde::Serialize;
+use serde_json::{
+    json,
+    Map,
+    Value,
+};
+
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    io::{
+        BufRead,
+        BufReader,
+        Read,
+        Write,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        OnceLock,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use crate::structs::*;
+
+/// The number of objects start.gg allows a single request to return.
+pub const DEFAULT_OBJECT_LIMIT: usize = 1000;
+
+/// The data a mock server answers queries from.
+///
+/// Objects nested anywhere in the data can be queried by id, so an event only needs to be part of a tournament to be found.
+#[derive(Clone, Default, Serialize)]
+pub struct MockData {
+
+    pub tournaments:    Vec<GGTournament>,
+    pub users:          Vec<GGUser>,
+    pub players:        Vec<GGPlayer>,
+    pub videogames:     Vec<GGVideogame>,
+
+}
+
+/// The state of a mock server, shared with the listener.
+struct MockState {
+    data:           MockData,
+    value:          Value,
+    object_limit:   usize,
+    rate_limit:     Option<(usize, Duration)>,
+    requests:       VecDeque<Instant>,
+    total_requests: usize,
+}
+
+type Servers = Arc<Mutex<HashMap<String, Arc<Mutex<MockState>>>>>;
+
+/// The address of the shared listener and the servers registered with it.
+static LISTENER: OnceLock<(String, Servers)> = OnceLock::new();
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// A mock start.gg server.
+///
+/// The server is removed from the listener when dropped, and queries go back to start.gg's API if no other server is running.
+pub struct MockServer {
+    token:  String,
+    state:  Arc<Mutex<MockState>>,
+}
+
+impl MockServer {
+
+    /// Start a mock server answering queries from the given data.
+    ///
+    /// Also sets the endpoint of the crate to the mock server, saving the previous endpoint if no other server is running.
+    pub fn start(data: MockData) -> MockServer {
+        let (endpoint, servers) = LISTENER.get_or_init(listen);
+        let token = format!("ggapi-mock-{}", NEXT_TOKEN.fetch_add(1, Ordering::SeqCst));
+        let state = Arc::new(Mutex::new(MockState {
+            value:          serde_json::to_value(&data).unwrap(),
+            data:           data,
+            object_limit:   DEFAULT_OBJECT_LIMIT,
+            rate_limit:     None,
+            requests:       VecDeque::new(),
+            total_requests: 0,
+        }));
+        let mut servers = servers.lock().unwrap();
+        servers.insert(token.clone(), state.clone());
+        crate::set_endpoint(endpoint);
+        return MockServer { token: token, state: state };
+    }
+
+    /// Returns the token queries to this server must be sent with.
+    pub fn token(&self) -> &str {
+        return &self.token;
+    }
+
+    /// Returns the endpoint of the mock server.
+    pub fn endpoint(&self) -> String {
+        return LISTENER.get().unwrap().0.clone();
+    }
+
+    /// Change the data of the server.
+    ///
+    /// Useful for simulating changes to a tournament between requests.
+    pub fn update(&self, update: impl FnOnce(&mut MockData)) {
+        let mut state = self.state.lock().unwrap();
+        update(&mut state.data);
+        state.value = serde_json::to_value(&state.data).unwrap();
+    }
+
+    /// Replace the data of the server.
+    pub fn set_data(&self, data: MockData) {
+        self.update(|current| *current = data);
+    }
+
+    /// Set the maximum number of objects a single request can return before it fails.
+    ///
+    /// Defaults to start.gg's limit of 1000 objects.
+    pub fn set_object_limit(&self, limit: usize) {
+        self.state.lock().unwrap().object_limit = limit;
+    }
+
+    /// Limit the number of requests that can be made within a window.
+    ///
+    /// Requests over the limit fail the same way start.gg's rate limit does. There is no limit by default.
+    pub fn set_rate_limit(&self, requests: usize, window: Duration) {
+        self.state.lock().unwrap().rate_limit = Some((requests, window));
+    }
+
+    /// Returns the number of requests the server has received, including any that failed.
+    pub fn requests(&self) -> usize {
+        return self.state.lock().unwrap().total_requests;
+    }
+
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some((_, servers)) = LISTENER.get() {
+            let mut servers = servers.lock().unwrap();
+            servers.remove(&self.token);
+            if servers.is_empty() {
+                crate::set_endpoint("");
+            }
+        }
+    }
+}
+
+/// Start the shared listener on a local port.
+fn listen() -> (String, Servers) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("The mock server could not bind to a local port");
+    let endpoint = format!("http://{}/gql/alpha", listener.local_addr().unwrap());
+    let servers: Servers = Default::default();
+    let shared = servers.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let servers = shared.clone();
+            thread::spawn(move || handle(stream, servers));
+        }
+    });
+    return (endpoint, servers);
+}
+
+/// Read a request, answer it and close the connection.
+fn handle(stream: TcpStream, servers: Servers) {
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(stream) => stream,
+        Err(_) => return,
+    });
+    let mut token = String::new();
+    let mut length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "authorization" => token = value.trim().trim_start_matches("Bearer ").to_string(),
+                "content-length" => length = value.trim().parse().unwrap_or(0),
+                _ => {},
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let state = servers.lock().unwrap().get(&token).cloned();
+    let (status, response) = match state {
+        Some(state) => respond(&mut state.lock().unwrap(), &body),
+        None => (401, error("Invalid authentication token")),
+    };
+    let reason = match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        429 => "Too Many Requests",
+        _ => "Bad Request",
+    };
+    let response = response.to_string();
+    let mut stream = stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, response.len(), response,
+    );
+}
+
+/// Returns a response containing a single error.
+fn error(message: &str) -> Value {
+    return json!({ "success": false, "message": message, "errors": [{ "message": message }] });
+}
+
+/// Answer a request, returning the status and body of the response.
+fn respond(state: &mut MockState, body: &[u8]) -> (u16, Value) {
+    state.total_requests += 1;
+    if let Some((requests, window)) = state.rate_limit {
+        let now = Instant::now();
+        while state.requests.front().is_some_and(|time| now.duration_since(*time) >= window) {
+            state.requests.pop_front();
+        }
+        if state.requests.len() >= requests {
+            return (429, error("Rate limit exceeded - api-token-rate-limit"));
+        }
+        state.requests.push_back(now);
+    }
+
+    let request: Value = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(_) => return (400, error("The request body is not valid JSON")),
+    };
+    let query = request["query"].as_str().unwrap_or("");
+    let vars = &request["variables"];
+    if query.trim_start().starts_with("mutation") {
+        return (200, json!({ "data": null, "errors": [{ "message": "Mutations are not supported by the mock server" }] }));
+    }
+
+    let fields = match parse_query(query) {
+        Some(fields) => fields,
+        None => return (400, error("The query could not be parsed")),
+    };
+    let mut data = Map::new();
+    for field in &fields {
+        let value = match resolve_root(&state.value, field, vars) {
+            Some(value) => project(&value, field, vars),
+            None => Value::Null,
+        };
+        data.insert(field.key().to_string(), value);
+    }
+    let data = Value::Object(data);
+
+    if count_objects(&data) > state.object_limit {
+        let message = format!("Your query complexity is too high. A maximum of {} objects may be returned by each request.", state.object_limit);
+        return (200, json!({ "data": null, "errors": [{ "message": message }] }));
+    }
+    return (200, json!({ "data": data }));
+}
+
+/// A field selected by a query.
+struct Field {
+    alias:      Option<String>,
+    name:       String,
+    args:       String,
+    children:   Vec<Field>,
+}
+
+impl Field {
+
+    /// Returns the key of the field in the response.
+    fn key(&self) -> &str {
+        return self.alias.as_deref().unwrap_or(&self.name);
+    }
+
+}
+
+/// Returns the fields selected by the first operation of a query.
+fn parse_query(query: &str) -> Option<Vec<Field>> {
+    let query: String = query.lines().map(|line| line.split('#').next().unwrap_or("")).collect::<Vec<_>>().join("\n");
+    let start = query.find('{')?;
+    let mut chars = query[start + 1..].chars().peekable();
+    return parse_selection(&mut chars);
+}
+
+/// Parses a selection set, after its opening brace.
+fn parse_selection(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Vec<Field>> {
+    let mut fields = Vec::new();
+    loop {
+        skip_whitespace(chars);
+        match chars.peek()? {
+            '}' => {
+                chars.next();
+                return Some(fields);
+            },
+            '.' => {
+                // Fragments aren't supported, so spreads are skipped along with their type condition.
+                while chars.peek() == Some(&'.') {
+                    chars.next();
+                }
+                skip_whitespace(chars);
+                read_name(chars);
+                continue;
+            },
+            _ => {},
+        }
+
+        let mut name = read_name(chars);
+        if name.is_empty() {
+            return None;
+        }
+        let mut alias = None;
+        skip_whitespace(chars);
+        if chars.peek() == Some(&':') {
+            chars.next();
+            skip_whitespace(chars);
+            alias = Some(name);
+            name = read_name(chars);
+            skip_whitespace(chars);
+        }
+        let mut args = String::new();
+        if chars.peek() == Some(&'(') {
+            chars.next();
+            let mut depth = 1;
+            for c in chars.by_ref() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {},
+                }
+                if depth == 0 {
+                    break;
+                }
+                args.push(c);
+            }
+            skip_whitespace(chars);
+        }
+        let mut children = Vec::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            children = parse_selection(chars)?;
+        }
+        fields.push(Field { alias: alias, name: name, args: args, children: children });
+    }
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ',') {
+        chars.next();
+    }
+}
+
+fn read_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) = chars.peek() {
+        if !c.is_alphanumeric() && *c != '_' {
+            break;
+        }
+        name.push(*c);
+        chars.next();
+    }
+    return name;
+}
+
+/// Returns the value of an argument, found at any depth of the arguments of a field.
+///
+/// Variables are replaced with their value.
+fn argument(args: &str, name: &str, vars: &Value) -> Option<Value> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = args.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            let mut string = String::from('"');
+            for c in chars.by_ref() {
+                string.push(c);
+                if c == '"' {
+                    break;
+                }
+            }
+            tokens.push(string);
+        } else if c.is_alphanumeric() || c == '_' || c == '$' || c == '-' || c == '.' {
+            let mut token = String::from(c);
+            while let Some(c) = chars.peek() {
+                if !c.is_alphanumeric() && *c != '_' && *c != '-' && *c != '.' {
+                    break;
+                }
+                token.push(*c);
+                chars.next();
+            }
+            tokens.push(token);
+        } else if !c.is_whitespace() && c != ',' {
+            tokens.push(c.to_string());
+        }
+    }
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token == name && tokens.get(i + 1).map(|token| token.as_str()) == Some(":") {
+            let value = tokens.get(i + 2)?;
+            if let Some(var) = value.strip_prefix('$') {
+                return match &vars[var] {
+                    Value::Null => None,
+                    value => Some(value.clone()),
+                };
+            }
+            if let Some(string) = value.strip_prefix('"') {
+                return Some(Value::String(string.trim_end_matches('"').to_string()));
+            }
+            if let Ok(number) = value.parse::<i64>() {
+                return Some(Value::from(number));
+            }
+            return Some(Value::String(value.clone()));
+        }
+    }
+
+    // Input objects can also be given as a single variable, such as `query: $query`.
+    for token in &tokens {
+        if let Some(value) = token.strip_prefix('$').and_then(|var| find_key(&vars[var], name)) {
+            return Some(value);
+        }
+    }
+    return None;
+}
+
+/// Returns the value of a key found at any depth of a variable.
+fn find_key(value: &Value, name: &str) -> Option<Value> {
+    let object = value.as_object()?;
+    if let Some(value) = object.get(name).filter(|value| !value.is_null()) {
+        return Some(value.clone());
+    }
+    return object.values().find_map(|value| find_key(value, name));
+}
+
+/// Returns a value as a string, without quotes.
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(string) => return string.clone(),
+        value => return value.to_string(),
+    }
+}
+
+/// Returns the keys objects of a root field can be found under.
+fn root_keys(name: &str) -> &'static [&'static str] {
+    match name {
+        "tournament" => return &["tournaments", "tournament"],
+        "event" => return &["events", "event"],
+        "phase" => return &["phases", "phase"],
+        "phaseGroup" => return &["phaseGroups", "phaseGroup"],
+        "set" => return &["sets", "set"],
+        "entrant" => return &["entrants", "entrant"],
+        "participant" => return &["participants", "participant"],
+        "player" => return &["players", "player"],
+        "user" => return &["users", "user", "owner", "admins"],
+        "videogame" => return &["videogames", "videogame"],
+        _ => return &[],
+    }
+}
+
+/// Resolve a root field of a query.
+fn resolve_root(data: &Value, field: &Field, vars: &Value) -> Option<Value> {
+    match field.name.as_str() {
+        "tournaments" => return Some(json!({ "nodes": data["tournaments"].clone() })),
+        "videogames" => {
+            let mut videogames = data["videogames"].as_array().cloned().unwrap_or_default();
+            if let Some(name) = argument(&field.args, "name", vars) {
+                let name = plain(&name).to_lowercase();
+                videogames.retain(|videogame| {
+                    ["name", "displayName"].iter().any(|key| videogame[key].as_str().is_some_and(|value| value.to_lowercase().contains(&name)))
+                });
+            }
+            return Some(json!({ "nodes": videogames }));
+        },
+        _ => {},
+    }
+
+    let keys = root_keys(&field.name);
+    let id = argument(&field.args, "id", vars).map(|id| plain(&id)).filter(|id| !id.is_empty() && id != "0");
+    let slug = argument(&field.args, "slug", vars).map(|slug| plain(&slug)).filter(|slug| !slug.is_empty());
+    if keys.is_empty() || (id.is_none() && slug.is_none()) {
+        return None;
+    }
+    let matches = |object: &Map<String, Value>| {
+        if let Some(id) = &id {
+            return object.get("id").is_some_and(|value| !value.is_null() && plain(value) == *id);
+        }
+        let slug = slug.as_ref().unwrap();
+        return object.get("slug").and_then(|value| value.as_str()).is_some_and(|value| value == slug || value.ends_with(&format!("/{}", slug)))
+            || object.get("shortSlug").and_then(|value| value.as_str()) == Some(slug.as_str());
+    };
+
+    let mut best: Option<Value> = None;
+    let mut ancestors: Vec<(&str, &Map<String, Value>)> = Vec::new();
+    if let Some(root) = data.as_object() {
+        for (key, value) in root {
+            find(value, key, keys, &matches, &mut ancestors, &mut best);
+        }
+    }
+    return best;
+}
+
+/// Search for the most complete object under one of the keys which matches.
+///
+/// Objects nested in a parent, such as the sets of a phase group, have missing references to their parent filled in.
+fn find<'a>(
+    value: &'a Value,
+    key: &'a str,
+    keys: &[&str],
+    matches: &dyn Fn(&Map<String, Value>) -> bool,
+    ancestors: &mut Vec<(&'a str, &'a Map<String, Value>)>,
+    best: &mut Option<Value>,
+) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                find(value, key, keys, matches, ancestors, best);
+            }
+        },
+        Value::Object(object) if object.get("nodes").is_some_and(|nodes| nodes.is_array()) => {
+            find(&object["nodes"], key, keys, matches, ancestors, best);
+        },
+        Value::Object(object) => {
+            if keys.contains(&key) && matches(object) {
+                let mut found = object.clone();
+                for (field, parent) in [("tournament", "tournaments"), ("event", "events"), ("phase", "phases"), ("phaseGroup", "phaseGroups")] {
+                    if found.get(field) != Some(&Value::Null) {
+                        continue;
+                    }
+                    if let Some((_, parent)) = ancestors.iter().rev().find(|(key, _)| *key == parent) {
+                        let shallow: Map<String, Value> = parent.iter().filter(|(_, value)| !value.is_object() && !value.is_array()).map(|(key, value)| (key.clone(), value.clone())).collect();
+                        found.insert(field.to_string(), Value::Object(shallow));
+                    }
+                }
+                let size = |object: &Map<String, Value>| object.values().filter(|value| !value.is_null()).count();
+                if best.as_ref().and_then(|best| best.as_object()).is_none_or(|best| size(best) < size(&found)) {
+                    *best = Some(Value::Object(found));
+                }
+            }
+            ancestors.push((key, object));
+            for (key, value) in object {
+                find(value, key, keys, matches, ancestors, best);
+            }
+            ancestors.pop();
+        },
+        _ => {},
+    }
+}
+
+/// Returns only the selected fields of a value, paginating any connections.
+fn project(value: &Value, field: &Field, vars: &Value) -> Value {
+    if field.children.is_empty() {
+        return value.clone();
+    }
+    match value {
+        Value::Array(values) => return Value::Array(values.iter().map(|value| project(value, field, vars)).collect()),
+        Value::Object(object) => {
+            let mut object = object.clone();
+            if let Some(Value::Array(nodes)) = object.get("nodes").cloned() {
+                let total = nodes.len() as i64;
+                let per_page = argument(&field.args, "perPage", vars).and_then(|value| value.as_i64()).filter(|per_page| *per_page > 0).unwrap_or(total.max(1));
+                let page = argument(&field.args, "page", vars).and_then(|value| value.as_i64()).unwrap_or(1).max(1);
+                let start = ((page - 1) * per_page).min(total) as usize;
+                let end = (page * per_page).min(total) as usize;
+                object.insert("nodes".to_string(), Value::Array(nodes[start..end].to_vec()));
+                object.insert("pageInfo".to_string(), json!({
+                    "total": total,
+                    "totalPages": (total + per_page - 1) / per_page,
+                    "page": page,
+                    "perPage": per_page,
+                }));
+            }
+            let mut result = Map::new();
+            for child in &field.children {
+                let value = object.get(&child.name).unwrap_or(&Value::Null);
+                result.insert(child.key().to_string(), project(value, child, vars));
+            }
+            return Value::Object(result);
+        },
+        value => return value.clone(),
+    }
+}
+
+/// Returns the number of objects in a response.
+fn count_objects(value: &Value) -> usize {
+    match value {
+        Value::Array(values) => return values.iter().map(count_objects).sum(),
+        Value::Object(object) => return 1 + object.values().map(count_objects).sum::<usize>(),
+        _ => return 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        enums::*,
+        GGResponse,
+    };
+
+    fn data() -> MockData {
+        let standings = (1..=120).map(|placement| GGStanding {
+            id: Some(placement),
+            placement: Some(placement),
+            entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(1000 + placement)), name: Some(format!("Player {}", placement)), ..Default::default() })),
+            ..Default::default()
+        }).collect();
+        let set = GGSet { id: Some(GGID::Int(7)), full_round_text: Some("Winners Final".to_string()), ..Default::default() };
+        let phase_group = GGPhaseGroup {
+            id: Some(GGID::Int(5)),
+            display_identifier: Some("A1".to_string()),
+            sets: Some(GGSetConnection { nodes: vec![set], ..Default::default() }),
+            ..Default::default()
+        };
+        let phase = GGPhase {
+            id: Some(GGID::Int(4)),
+            name: Some("Pools".to_string()),
+            phase_groups: Some(GGPhaseGroupConnection { nodes: vec![phase_group], page_info: None }),
+            ..Default::default()
+        };
+        let event = GGEvent {
+            id: Some(GGID::Int(3)),
+            name: Some("Singles".to_string()),
+            phases: Some(vec![phase]),
+            standings: Some(GGStandingConnection { nodes: standings, ..Default::default() }),
+            ..Default::default()
+        };
+        let tournament = GGTournament {
+            id: Some(GGID::Int(2)),
+            name: Some("Mock Major".to_string()),
+            slug: Some("tournament/mock-major".to_string()),
+            events: Some(vec![event]),
+            ..Default::default()
+        };
+        return MockData { tournaments: vec![tournament], ..Default::default() };
+    }
+
+    #[tokio::test]
+    async fn queries_are_answered_from_the_data() {
+        let server = MockServer::start(data());
+
+        match crate::get_tournament_info("mock-major", server.token()).await {
+            GGResponse::Data(data) => {
+                let tournament = data.tournament();
+                assert_eq!(tournament.name(), "Mock Major");
+                assert_eq!(tournament.events()[0].phases()[0].phase_groups()[0].display_identifier(), "A1");
+            },
+            GGResponse::Error(e) => panic!("{}", e),
+        }
+
+        match crate::get_phase_phase_groups(GGID::Int(4), Default::default(), server.token()).await {
+            GGResponse::Data(data) => assert_eq!(data.phase().phase_groups()[0].display_identifier(), "A1"),
+            GGResponse::Error(e) => panic!("{}", e),
+        }
+
+        match crate::get_entrants_from_set(GGID::Int(7), server.token()).await {
+            GGResponse::Data(data) => assert_eq!(data.set().event().name(), "Singles"),
+            GGResponse::Error(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn endpoint_is_kept_while_a_server_is_running() {
+        let first = MockServer::start(data());
+        let second = MockServer::start(data());
+        drop(second);
+        assert_eq!(crate::endpoint(), first.endpoint());
+    }
+
+    #[tokio::test]
+    async fn connections_are_paginated() {
+        let server = MockServer::start(data());
+
+        match crate::get_event_standings(GGID::Int(3), Default::default(), server.token()).await {
+            GGResponse::Data(data) => assert_eq!(data.event().standings().len(), 120),
+            GGResponse::Error(e) => panic!("{}", e),
+        }
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn player_profile_is_fetched_once() {
+        let sets = (1..=60).map(|id| GGSet { id: Some(GGID::Int(id)), ..Default::default() }).collect();
+        let player = GGPlayer {
+            id: Some(9),
+            gamer_tag: Some("Mock".to_string()),
+            rankings: Some(vec![GGPlayerRank { id: Some(1), rank: Some(3), ..Default::default() }]),
+            sets: Some(GGSetConnection { nodes: sets, ..Default::default() }),
+            ..Default::default()
+        };
+        let server = MockServer::start(MockData { players: vec![player], ..Default::default() });
+
+        match crate::get_player(GGID::Int(9), Default::default(), server.token()).await {
+            GGResponse::Data(data) => {
+                assert_eq!(data.player().rankings()[0].rank(), 3);
+                assert_eq!(data.player().sets().len(), 60);
+            },
+            GGResponse::Error(e) => panic!("{}", e),
+        }
+        // One request for the profile and one for each page of 25 sets.
+        assert_eq!(server.requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn watcher_emits_changes_between_polls() {
+        let server = MockServer::start(data());
+        let mut watcher = crate::EventWatcher::new(crate::WatchTarget::PhaseGroup(GGID::Int(5)));
+        assert!(watcher.poll(server.token()).await.ok().unwrap().is_empty());
+
+        server.update(|data| {
+            let phase_group = &mut data.tournaments[0].events.as_mut().unwrap()[0].phases.as_mut().unwrap()[0].phase_groups.as_mut().unwrap().nodes[0];
+            phase_group.state = Some(GGActivityState::Completed as i64);
+            phase_group.sets.as_mut().unwrap().nodes[0].state = Some(GGActivityState::Active as i64);
+        });
+        let events = watcher.poll(server.token()).await.ok().unwrap();
+        assert!(matches!(events[..], [crate::WatchEvent::SetStarted(_), crate::WatchEvent::PhaseGroupCompleted(_)]));
+    }
+
+    #[tokio::test]
+    async fn watcher_keeps_changes_when_a_poll_fails() {
+        let event = |id: i64, set_id: i64| GGEvent {
+            id: Some(GGID::Int(id)),
+            sets: Some(GGSetConnection { nodes: vec![GGSet { id: Some(GGID::Int(set_id)), ..Default::default() }], ..Default::default() }),
+            ..Default::default()
+        };
+        let tournament = GGTournament { id: Some(GGID::Int(2)), events: Some(vec![event(3, 30), event(4, 40)]), ..Default::default() };
+        let server = MockServer::start(MockData { tournaments: vec![tournament], ..Default::default() });
+        let mut watcher = crate::EventWatcher::new(crate::WatchTarget::Tournament(GGID::Int(2)));
+        assert!(watcher.poll(server.token()).await.ok().unwrap().is_empty());
+
+        server.update(|data| {
+            let set = &mut data.tournaments[0].events.as_mut().unwrap()[0].sets.as_mut().unwrap().nodes[0];
+            set.state = Some(GGActivityState::Active as i64);
+        });
+        // The structure and the first event are fetched, then the second event's query fails.
+        server.set_rate_limit(2, Duration::from_secs(60));
+        assert!(watcher.poll(server.token()).await.is_err());
+
+        server.set_rate_limit(100, Duration::from_secs(60));
+        let events = watcher.poll(server.token()).await.ok().unwrap();
+        assert!(matches!(&events[..], [crate::WatchEvent::SetStarted(set)] if set.id().to_string() == "30"));
+    }
+
+    #[tokio::test]
+    async fn limits_are_simulated() {
+        let server = MockServer::start(data());
+
+        server.set_object_limit(10);
+        match crate::get_event_standings(GGID::Int(3), Default::default(), server.token()).await {
+            GGResponse::Data(_) => panic!("The object limit was not applied"),
+            GGResponse::Error(e) => assert!(e.contains("A maximum of 10 objects")),
+        }
+
+        server.set_object_limit(DEFAULT_OBJECT_LIMIT);
+        server.set_rate_limit(1, Duration::from_secs(60));
+        assert!(matches!(crate::get_tournament_info("mock-major", server.token()).await, GGResponse::Data(_)));
+        match crate::get_tournament_info("mock-major", server.token()).await {
+            GGResponse::Data(_) => panic!("The rate limit was not applied"),
+            GGResponse::Error(e) => assert!(e.contains("Rate limit exceeded")),
+        }
+    }
+}
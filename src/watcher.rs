@@ -0,0 +1,332 @@
+//! Watching a tournament, event or phase group for changes, used to react to sets being called, played and reported as they happen.
+//!
+//! The watcher polls start.gg, compares each snapshot with the previous one and emits the changes as typed events.
+
+use futures::Stream;
+use futures_timer::Delay;
+
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    time::Duration,
+};
+
+use crate::{
+    enums::*,
+    execute_query,
+    set_sync::*,
+    structs::*,
+    Vars,
+};
+
+/// What an event watcher watches.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum WatchTarget {
+
+    Tournament(GGID),
+    Event(GGID),
+    PhaseGroup(GGID),
+
+}
+
+/// A change seen by an event watcher.
+#[derive(Clone)]
+pub enum WatchEvent {
+
+    /// The set was called to a station.
+    SetCalled(GGSet),
+
+    /// The set was started.
+    SetStarted(GGSet),
+
+    /// The set was reported, with the winning entrant and the display score.
+    SetCompleted {
+        set: GGSet,
+        winner: GGEntrant,
+        score: String,
+    },
+
+    /// The set was reset after being called, started or reported.
+    SetReset(GGSet),
+
+    /// An entrant was disqualified from the set.
+    EntrantDQ {
+        set: GGSet,
+        entrant: GGEntrant,
+    },
+
+    /// Every set in the phase group was completed.
+    PhaseGroupCompleted(GGPhaseGroup),
+
+    /// The set was assigned to a stream.
+    StreamAssigned {
+        set: GGSet,
+        stream: GGStreams,
+    },
+
+    /// The set was assigned to a station.
+    StationAssigned {
+        set: GGSet,
+        station: GGStations,
+    },
+
+}
+
+/// Polls a tournament, event or phase group and emits what changed between polls.
+///
+/// The first poll only records the current state, so events are emitted for changes made after the watcher started.
+/// Sets are synced incrementally, see SetSync.
+pub struct EventWatcher {
+
+    target:         WatchTarget,
+    sync:           SetSync,
+    phase_groups:   HashMap<GGID, GGPhaseGroup>,
+    started:        bool,
+
+}
+
+impl EventWatcher {
+
+    /// Creates a watcher for a tournament, event or phase group.
+    pub fn new(target: WatchTarget) -> EventWatcher {
+        return EventWatcher {
+            target:         target,
+            sync:           SetSync::new(),
+            phase_groups:   HashMap::new(),
+            started:        false,
+        };
+    }
+
+    /// Returns the synced sets of the watched tournament, event or phase group.
+    pub fn sync(&self) -> &SetSync {
+        return &self.sync;
+    }
+
+    /// Polls for changes since the last poll.
+    ///
+    /// Returns the events in the order they were found, or the error message if a query failed.
+    /// A failed poll doesn't change what the watcher has seen, so its events are returned by the next successful poll.
+    /// The first poll always returns no events.
+    pub async fn poll(&mut self, token: &str) -> Result<Vec<WatchEvent>, String> {
+        let (phase_groups, sync_targets) = self.structure(token).await?;
+
+        // Every target is fetched before any is applied, so a failed query leaves the watcher as it was and the next poll sees the same changes.
+        let mut fetched: Vec<(SyncTarget, Vec<GGSet>)> = Vec::new();
+        for target in sync_targets {
+            let sets = self.sync.fetch(&target, token).await?;
+            fetched.push((target, sets));
+        }
+
+        let mut events: Vec<WatchEvent> = Vec::new();
+        for (target, sets) in fetched {
+            if self.started {
+                for set in &sets {
+                    let before = self.sync.set(&set.id()).cloned().unwrap_or_default();
+                    events.append(&mut set_events(&before, set));
+                }
+            }
+            self.sync.apply(target, sets);
+        }
+
+        let completed = GGActivityState::Completed as i64;
+        for phase_group in phase_groups {
+            let was_completed = self.phase_groups.get(&phase_group.id()).is_some_and(|before| before.state() == completed);
+            if self.started && !was_completed && phase_group.state() == completed {
+                events.push(WatchEvent::PhaseGroupCompleted(phase_group.clone()));
+            }
+            self.phase_groups.insert(phase_group.id(), phase_group);
+        }
+
+        self.started = true;
+        return Ok(events);
+    }
+
+    /// Polls for changes on an interval, emitting each event as it is found.
+    ///
+    /// A failed poll emits the error message and the watcher keeps polling, so the stream only ends when it is dropped.
+    pub fn watch(self, interval: Duration, token: &str) -> impl Stream<Item = Result<WatchEvent, String>> {
+        let state = (self, token.to_string(), VecDeque::<WatchEvent>::new(), true);
+
+        return futures::stream::unfold(state, move |(mut watcher, token, mut events, first)| async move {
+            let mut first = first;
+            loop {
+                if let Some(event) = events.pop_front() {
+                    return Some((Ok(event), (watcher, token, events, first)));
+                }
+                if !first {
+                    Delay::new(interval).await;
+                }
+                first = false;
+                match watcher.poll(&token).await {
+                    Ok(found) => events.extend(found),
+                    Err(e) => return Some((Err(e), (watcher, token, events, first))),
+                }
+            }
+        });
+    }
+
+    /// Fetches the phase groups of the target and what its sets should be synced from.
+    async fn structure(&self, token: &str) -> Result<(Vec<GGPhaseGroup>, Vec<SyncTarget>), String> {
+        let (query, id) = match &self.target {
+            WatchTarget::Tournament(id) => (r#"
+            query WatchTournament($id: ID!) {
+                tournament(id: $id) {
+                    id
+                    events {
+                        id
+                        phaseGroups {
+                            id
+                            displayIdentifier
+                            state
+                        }
+                    }
+                }
+            }
+            "#, id),
+            WatchTarget::Event(id) => (r#"
+            query WatchEvent($id: ID!) {
+                event(id: $id) {
+                    id
+                    phaseGroups {
+                        id
+                        displayIdentifier
+                        state
+                    }
+                }
+            }
+            "#, id),
+            WatchTarget::PhaseGroup(id) => (r#"
+            query WatchPhaseGroup($id: ID!) {
+                phaseGroup(id: $id) {
+                    id
+                    displayIdentifier
+                    state
+                }
+            }
+            "#, id),
+        };
+
+        let vars = Vars { id: id.clone(), slug: "".to_string(), page: 1, per_page: 100 };
+
+        let data = match execute_query(token, query, vars).await {
+            GGResponse::Data(data) => data,
+            GGResponse::Error(e) => return Err(e),
+        };
+        match &self.target {
+            WatchTarget::Tournament(_) => {
+                let events = data.tournament().events();
+                let phase_groups = events.iter().flat_map(|event| event.phase_groups()).collect();
+                return Ok((phase_groups, events.iter().map(|event| SyncTarget::Event(event.id())).collect()));
+            },
+            WatchTarget::Event(id) => return Ok((data.event().phase_groups(), vec![SyncTarget::Event(id.clone())])),
+            WatchTarget::PhaseGroup(id) => return Ok((vec![data.phase_group()], vec![SyncTarget::PhaseGroup(id.clone())])),
+        }
+    }
+
+}
+
+/// Returns the entrant in one of a set's slots.
+fn slot_entrant(set: &GGSet, is_winner: bool) -> Option<GGEntrant> {
+    let winner_id = set.winner_id().to_string();
+    return set.slots().into_iter()
+        .filter(|slot| slot.entrant.is_some())
+        .map(|slot| slot.entrant())
+        .find(|entrant| (entrant.id().to_string() == winner_id) == is_winner);
+}
+
+/// Returns the events for the changes between two versions of a set.
+///
+/// A set that wasn't seen before is compared against an empty set.
+pub fn set_events(before: &GGSet, after: &GGSet) -> Vec<WatchEvent> {
+    let mut events: Vec<WatchEvent> = Vec::new();
+    let state = |set: &GGSet| GGActivityState::try_from(set.state()).ok();
+
+    if state(before) != state(after) {
+        match state(after) {
+            Some(GGActivityState::Called) => events.push(WatchEvent::SetCalled(after.clone())),
+            Some(GGActivityState::Active) => events.push(WatchEvent::SetStarted(after.clone())),
+            Some(GGActivityState::Completed) => {
+                if let Some(winner) = slot_entrant(after, true) {
+                    events.push(WatchEvent::SetCompleted { set: after.clone(), winner: winner, score: after.display_score() });
+                }
+                if after.display_score() == "DQ" {
+                    if let Some(entrant) = slot_entrant(after, false) {
+                        events.push(WatchEvent::EntrantDQ { set: after.clone(), entrant: entrant });
+                    }
+                }
+            },
+            Some(GGActivityState::Created) | Some(GGActivityState::Ready) | Some(GGActivityState::Queued) => {
+                if matches!(state(before), Some(GGActivityState::Called) | Some(GGActivityState::Active) | Some(GGActivityState::Completed)) {
+                    events.push(WatchEvent::SetReset(after.clone()));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    let station_id = |set: &GGSet| set.station.as_ref().and_then(|station| station.id);
+    if station_id(after).is_some() && station_id(before) != station_id(after) {
+        events.push(WatchEvent::StationAssigned { set: after.clone(), station: after.station() });
+    }
+    let stream_id = |set: &GGSet| set.stream.as_ref().and_then(|stream| stream.id);
+    if stream_id(after).is_some() && stream_id(before) != stream_id(after) {
+        events.push(WatchEvent::StreamAssigned { set: after.clone(), stream: after.stream() });
+    }
+    return events;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(state: GGActivityState, winner_id: Option<i64>, display_score: &str) -> GGSet {
+        let slot = |id: i64| GGSetSlot {
+            entrant: Some(Box::new(GGEntrant { id: Some(GGID::Int(id)), name: Some(format!("Entrant {}", id)), ..Default::default() })),
+            ..Default::default()
+        };
+        return GGSet {
+            id: Some(GGID::Int(1)),
+            state: Some(state as i64),
+            winner_id: winner_id,
+            display_score: Some(display_score.to_string()),
+            slots: Some(vec![slot(10), slot(20)]),
+            ..Default::default()
+        };
+    }
+
+    fn names(events: &[WatchEvent]) -> Vec<String> {
+        return events.iter().map(|event| match event {
+            WatchEvent::SetCalled(_) => "called".to_string(),
+            WatchEvent::SetStarted(_) => "started".to_string(),
+            WatchEvent::SetCompleted { winner, score, .. } => format!("completed {} {}", winner.name(), score),
+            WatchEvent::SetReset(_) => "reset".to_string(),
+            WatchEvent::EntrantDQ { entrant, .. } => format!("dq {}", entrant.name()),
+            WatchEvent::PhaseGroupCompleted(_) => "phase group completed".to_string(),
+            WatchEvent::StreamAssigned { stream, .. } => format!("stream {}", stream.id()),
+            WatchEvent::StationAssigned { station, .. } => format!("station {}", station.number()),
+        }).collect();
+    }
+
+    #[test]
+    fn set_state_changes_are_emitted() {
+        let created = set(GGActivityState::Created, None, "");
+        let mut called = set(GGActivityState::Called, None, "");
+        called.station = Some(Box::new(GGStations { id: Some(5), number: Some(3), ..Default::default() }));
+        let completed = set(GGActivityState::Completed, Some(20), "3 - 1");
+
+        assert_eq!(names(&set_events(&created, &called)), vec!["called", "station 3"]);
+        assert_eq!(names(&set_events(&called, &completed)), vec!["completed Entrant 20 3 - 1"]);
+        assert_eq!(names(&set_events(&completed, &created)), vec!["reset"]);
+        assert!(set_events(&completed, &completed).is_empty());
+    }
+
+    #[test]
+    fn disqualifications_are_emitted() {
+        let active = set(GGActivityState::Active, None, "");
+        let dq = set(GGActivityState::Completed, Some(10), "DQ");
+
+        assert_eq!(names(&set_events(&active, &dq)), vec!["completed Entrant 10 DQ", "dq Entrant 20"]);
+    }
+}